@@ -2,7 +2,7 @@ use strike_api::tipping::{tipping_request};
 extern crate qrcode_generator;
 use qrcode_generator::{QrCodeEcc};
 
-// Currently I only have a async version of tipping
+// For a synchronous version enable the blocking feature and use strike_api::blocking::tipping
 #[tokio::main]
 async fn main() {
 
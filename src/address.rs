@@ -0,0 +1,250 @@
+use crate::errors::LNError;
+use sha2::{Digest, Sha256};
+
+// Client side checks for bitcoin addresses so that obvious mistakes, such as a testnet
+// address sent to mainnet or a typo, never reach the Strike API.
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    P2pkh,
+    P2sh,
+    Segwit { version: u8 },
+}
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
+pub fn validate_address(address: &str, network: Network) -> Result<AddressType, LNError> {
+    let invalid = |reason: &str| LNError::InvalidAddress(format!("{}: {}", address, reason));
+
+    let hrp = match network {
+        Network::Mainnet => "bc",
+        Network::Testnet => "tb",
+    };
+
+    if address.get(..3).is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}1", hrp))) {
+        return validate_segwit_address(address, hrp).map_err(invalid);
+    }
+
+    let payload = base58check_decode(address).ok_or_else(|| invalid("invalid base58check encoding"))?;
+    if payload.len() != 21 {
+        return Err(invalid("invalid payload length"));
+    }
+    match (network, payload[0]) {
+        (Network::Mainnet, 0x00) | (Network::Testnet, 0x6f) => Ok(AddressType::P2pkh),
+        (Network::Mainnet, 0x05) | (Network::Testnet, 0xc4) => Ok(AddressType::P2sh),
+        _ => Err(invalid("address is not for this network")),
+    }
+}
+
+fn validate_segwit_address(address: &str, expected_hrp: &str) -> Result<AddressType, &'static str> {
+    if address.len() > 90 {
+        return Err("address is too long");
+    }
+    let (hrp, data, variant) = bech32_decode(address).ok_or("invalid bech32 encoding")?;
+    if hrp != expected_hrp {
+        return Err("address is not for this network");
+    }
+    let (version, program) = data.split_first().ok_or("missing witness version")?;
+    if *version > 16 {
+        return Err("invalid witness version");
+    }
+    let program = convert_bits(program, 5, 8, false).ok_or("invalid witness program")?;
+    if program.len() < 2 || program.len() > 40 {
+        return Err("invalid witness program length");
+    }
+    if *version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err("invalid witness program length");
+    }
+    match (version, variant) {
+        (0, Bech32Variant::Bech32) => Ok(AddressType::Segwit { version: 0 }),
+        (1..=16, Bech32Variant::Bech32m) => Ok(AddressType::Segwit { version: *version }),
+        _ => Err("wrong checksum variant for witness version"),
+    }
+}
+
+fn base58check_decode(input: &str) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+    let leading_zeros = input.bytes().take_while(|&c| c == b'1').count();
+    let mut decoded = vec![0u8; leading_zeros];
+    decoded.extend(bytes);
+
+    if decoded.len() < 4 {
+        return None;
+    }
+    let (payload, checksum) = decoded.split_at(decoded.len() - 4);
+    let hash = Sha256::digest(Sha256::digest(payload));
+    if &hash[..4] != checksum {
+        return None;
+    }
+    Some(payload.to_vec())
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATORS: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x01ff_ffff) << 5) ^ (*value as u32);
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+// Decodes a bech32 or bech32m string into its lowercase human readable part and 5 bit
+// data words, with the checksum removed. Length limits are left to the caller since
+// BOLT11 invoices are longer than addresses.
+pub(crate) fn bech32_decode(input: &str) -> Option<(String, Vec<u8>, Bech32Variant)> {
+    if input.chars().any(|c| c.is_ascii_lowercase()) && input.chars().any(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let input = input.to_ascii_lowercase();
+    let separator = input.rfind('1')?;
+    if separator == 0 || separator + 7 > input.len() {
+        return None;
+    }
+    let (hrp, data) = (&input[..separator], &input[separator + 1..]);
+    if hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return None;
+    }
+
+    let data = data
+        .bytes()
+        .map(|c| BECH32_CHARSET.iter().position(|&a| a == c).map(|p| p as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    let mut values: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|c| c & 31));
+    values.extend(&data);
+
+    let variant = match bech32_polymod(&values) {
+        BECH32_CONST => Bech32Variant::Bech32,
+        BECH32M_CONST => Bech32Variant::Bech32m,
+        _ => return None,
+    };
+    Some((hrp.to_string(), data[..data.len() - 6].to_vec(), variant))
+}
+
+#[cfg(test)]
+pub(crate) fn bech32_encode(hrp: &str, data: &[u8]) -> String {
+    let mut values: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    values.push(0);
+    values.extend(hrp.bytes().map(|c| c & 31));
+    values.extend(data);
+    values.extend([0u8; 6]);
+    let checksum = bech32_polymod(&values) ^ BECH32_CONST;
+
+    let mut encoded = format!("{}1", hrp);
+    for word in data.iter().copied().chain((0..6).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8)) {
+        encoded.push(BECH32_CHARSET[word as usize] as char);
+    }
+    encoded
+}
+
+pub(crate) fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut converted = Vec::new();
+    let max = (1 << to) - 1;
+    for value in data {
+        let value = *value as u32;
+        if value >> from != 0 {
+            return None;
+        }
+        acc = (acc << from) | value;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            converted.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            converted.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return None;
+    }
+    Some(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_mainnet_addresses() {
+        assert_eq!(validate_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", Network::Mainnet), Ok(AddressType::P2pkh));
+        assert_eq!(validate_address("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", Network::Mainnet), Ok(AddressType::P2sh));
+        assert_eq!(
+            validate_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Mainnet),
+            Ok(AddressType::Segwit { version: 0 })
+        );
+        assert_eq!(
+            validate_address("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", Network::Mainnet),
+            Ok(AddressType::Segwit { version: 0 })
+        );
+        assert_eq!(
+            validate_address("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", Network::Mainnet),
+            Ok(AddressType::Segwit { version: 1 })
+        );
+    }
+
+    #[test]
+    fn test_valid_testnet_addresses() {
+        assert_eq!(validate_address("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn", Network::Testnet), Ok(AddressType::P2pkh));
+        assert_eq!(validate_address("2MzQwSSnBHWHqSAqtTVQ6v47XtaisrJa1Vc", Network::Testnet), Ok(AddressType::P2sh));
+        assert_eq!(
+            validate_address("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", Network::Testnet),
+            Ok(AddressType::Segwit { version: 0 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        //Wrong network
+        assert!(validate_address("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", Network::Mainnet).is_err());
+        assert!(validate_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", Network::Testnet).is_err());
+        //Bad checksums
+        assert!(validate_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5", Network::Mainnet).is_err());
+        assert!(validate_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3", Network::Mainnet).is_err());
+        //Mixed case
+        assert!(validate_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7KV8F3T4", Network::Mainnet).is_err());
+        //Garbage
+        assert!(validate_address("", Network::Mainnet).is_err());
+        assert!(validate_address("not an address", Network::Mainnet).is_err());
+        assert!(validate_address("bc€1", Network::Mainnet).is_err());
+    }
+}
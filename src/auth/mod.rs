@@ -0,0 +1,49 @@
+use crate::errors::LNError;
+use async_trait::async_trait;
+
+pub mod oauth;
+
+// Strike accepts both API keys and OAuth access tokens as a bearer credential, so every
+// endpoint binding works with either. Bindings scoped to a client with a provider are
+// authorized by it, refreshing first if needed, and their api_key is ignored:
+//
+//     let client = Client::current().with_auth(provider);
+//     let rates = client.scope(get_rates("")).await?;
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait AuthProvider: Send + Sync {
+    // The bearer credential to use for the next request, refreshed first if needed
+    async fn access_token(&self) -> Result<String, LNError>;
+
+    // The scopes the credential was granted, or None when the provider cannot know them
+    fn scopes(&self) -> Option<Vec<String>> {
+        None
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiKeyProvider {
+    api_key: String,
+}
+
+impl ApiKeyProvider {
+    pub fn new<S: Into<String>>(api_key: S) -> Self {
+        ApiKeyProvider {
+            api_key: api_key.into(),
+        }
+    }
+}
+
+impl From<&str> for ApiKeyProvider {
+    fn from(api_key: &str) -> Self {
+        ApiKeyProvider::new(api_key)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthProvider for ApiKeyProvider {
+    async fn access_token(&self) -> Result<String, LNError> {
+        Ok(self.api_key.clone())
+    }
+}
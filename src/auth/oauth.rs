@@ -0,0 +1,416 @@
+use std::fmt;
+use std::sync::Mutex as SyncMutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::auth::AuthProvider;
+use crate::client::send_or_render;
+use crate::dry_run::placeholder_value;
+use crate::errors::{JsonError, LNError};
+use crate::redact::REDACTED;
+use crate::time::Instant;
+use crate::transport::{HttpRequest, Method};
+
+// OAuth 2.0 for Strike "Connect": authorization code with PKCE, token exchange and
+// refresh of the access token shortly before it expires.
+
+#[derive(Clone, PartialEq)]
+pub struct OAuthConfig {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    // How long before expiry the access token is refreshed
+    pub refresh_margin: Duration,
+}
+
+impl OAuthConfig {
+    pub fn new<S: Into<String>>(client_id: S, redirect_uri: S, scopes: Vec<String>) -> Self {
+        OAuthConfig {
+            client_id: client_id.into(),
+            client_secret: None,
+            redirect_uri: redirect_uri.into(),
+            scopes,
+            authorization_endpoint: String::from("https://auth.strike.me/connect/authorize"),
+            token_endpoint: String::from("https://auth.strike.me/connect/token"),
+            refresh_margin: Duration::from_secs(60),
+        }
+    }
+}
+
+impl fmt::Debug for OAuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OAuthConfig")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &self.client_secret.as_ref().map(|_| REDACTED))
+            .field("redirect_uri", &self.redirect_uri)
+            .field("scopes", &self.scopes)
+            .field("authorization_endpoint", &self.authorization_endpoint)
+            .field("token_endpoint", &self.token_endpoint)
+            .field("refresh_margin", &self.refresh_margin)
+            .finish()
+    }
+}
+
+// Proof Key for Code Exchange (RFC 7636), S256 only
+#[derive(Clone, PartialEq)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn new() -> Self {
+        let verifier: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(64)
+            .map(char::from)
+            .collect();
+        Pkce::from_verifier(verifier)
+    }
+
+    pub fn from_verifier<S: Into<String>>(verifier: S) -> Self {
+        let verifier = verifier.into();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Pkce { verifier, challenge }
+    }
+}
+
+// The challenge is public, the verifier is not
+impl fmt::Debug for Pkce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pkce")
+            .field("verifier", &REDACTED)
+            .field("challenge", &self.challenge)
+            .finish()
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Pkce::new()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct TokenSet {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Instant,
+    pub scopes: Vec<String>,
+}
+
+impl fmt::Debug for TokenSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TokenSet")
+            .field("access_token", &REDACTED)
+            .field("refresh_token", &self.refresh_token.as_ref().map(|_| REDACTED))
+            .field("expires_at", &self.expires_at)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+impl TokenSet {
+    fn expires_within(&self, margin: Duration) -> bool {
+        Instant::now() + margin >= self.expires_at
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+pub struct OAuthProvider {
+    config: OAuthConfig,
+    tokens: Mutex<Option<TokenSet>>,
+    // Kept outside of the async lock so scopes() can answer synchronously
+    scopes: SyncMutex<Option<Vec<String>>>,
+}
+
+impl OAuthProvider {
+    pub fn new(config: OAuthConfig) -> Self {
+        OAuthProvider {
+            config,
+            tokens: Mutex::new(None),
+            scopes: SyncMutex::new(None),
+        }
+    }
+
+    // Resume with tokens persisted from an earlier session
+    pub fn with_tokens(config: OAuthConfig, tokens: TokenSet) -> Self {
+        OAuthProvider {
+            config,
+            scopes: SyncMutex::new(Some(tokens.scopes.clone())),
+            tokens: Mutex::new(Some(tokens)),
+        }
+    }
+
+    // Where to send the user to grant access; state is echoed back to the redirect_uri
+    pub fn authorization_url(&self, state: &str, pkce: &Pkce) -> Result<String, LNError> {
+        let mut url = Url::parse(&self.config.authorization_endpoint).map_err(|e| LNError::AuthError(e.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("code_challenge", &pkce.challenge)
+            .append_pair("code_challenge_method", "S256");
+        Ok(url.to_string())
+    }
+
+    // Trades the code from the redirect for tokens, which the provider keeps from then on
+    pub async fn exchange_code(&self, code: &str, pkce: &Pkce) -> Result<TokenSet, LNError> {
+        let mut tokens = self.tokens.lock().await;
+        let token_set = self
+            .request_tokens(vec![
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_uri),
+                ("code_verifier", &pkce.verifier),
+            ])
+            .await?;
+        *tokens = Some(self.keep(token_set, None));
+        Ok(tokens.clone().unwrap())
+    }
+
+    pub async fn refresh(&self) -> Result<TokenSet, LNError> {
+        let mut tokens = self.tokens.lock().await;
+        let refreshed = self.refresh_locked(tokens.as_ref()).await?;
+        *tokens = Some(refreshed);
+        Ok(tokens.clone().unwrap())
+    }
+
+    pub async fn tokens(&self) -> Option<TokenSet> {
+        self.tokens.lock().await.clone()
+    }
+
+    async fn refresh_locked(&self, current: Option<&TokenSet>) -> Result<TokenSet, LNError> {
+        let current = current.ok_or_else(|| LNError::AuthError(String::from("not authorized yet")))?;
+        let refresh_token = current
+            .refresh_token
+            .clone()
+            .ok_or_else(|| LNError::AuthError(String::from("access token expired and there is no refresh token")))?;
+        let token_set = self
+            .request_tokens(vec![
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &refresh_token),
+            ])
+            .await?;
+        Ok(self.keep(token_set, Some(current)))
+    }
+
+    // Servers may omit the refresh token or scope when they do not change
+    fn keep(&self, mut token_set: TokenSet, previous: Option<&TokenSet>) -> TokenSet {
+        if let Some(previous) = previous {
+            if token_set.refresh_token.is_none() {
+                token_set.refresh_token = previous.refresh_token.clone();
+            }
+            if token_set.scopes.is_empty() {
+                token_set.scopes = previous.scopes.clone();
+            }
+        }
+        if token_set.scopes.is_empty() {
+            token_set.scopes = self.config.scopes.clone();
+        }
+        *self.scopes.lock().unwrap() = Some(token_set.scopes.clone());
+        token_set
+    }
+
+    async fn request_tokens(&self, mut form: Vec<(&str, &str)>) -> Result<TokenSet, LNError> {
+        form.push(("client_id", &self.config.client_id));
+        if let Some(client_secret) = &self.config.client_secret {
+            form.push(("client_secret", client_secret));
+        }
+        let mut request = HttpRequest::new(Method::Post, self.config.token_endpoint.clone());
+        request.set_header("Content-Type", "application/x-www-form-urlencoded");
+        request.body = Some(form_urlencoded::Serializer::new(String::new()).extend_pairs(&form).finish());
+        //A dry run is answered with a redacted token valid for an hour
+        let placeholder = TokenResponse {
+            access_token: REDACTED.to_string(),
+            expires_in: 3600,
+            refresh_token: None,
+            scope: None,
+        };
+        let response = send_or_render(request, || placeholder_value(&placeholder)).await?;
+
+        if !response.is_success() {
+            let message = match serde_json::from_str::<TokenErrorResponse>(&response.body) {
+                Ok(error) => format!("{}: {}", error.error, error.error_description.unwrap_or_default()),
+                Err(_) => format!("token endpoint returned {}", response.status),
+            };
+            return Err(LNError::AuthError(message));
+        }
+
+        let token_response: TokenResponse = serde_json::from_str(&response.body).map_err(|err| JsonError::new(err, &response.body))?;
+        Ok(TokenSet {
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token,
+            expires_at: Instant::now() + Duration::from_secs(token_response.expires_in),
+            scopes: token_response
+                .scope
+                .map(|scope| scope.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthProvider for OAuthProvider {
+    async fn access_token(&self) -> Result<String, LNError> {
+        let mut tokens = self.tokens.lock().await;
+        match tokens.as_ref() {
+            Some(current) if !current.expires_within(self.config.refresh_margin) => Ok(current.access_token.clone()),
+            current => {
+                let refreshed = self.refresh_locked(current).await?;
+                let access_token = refreshed.access_token.clone();
+                *tokens = Some(refreshed);
+                Ok(access_token)
+            }
+        }
+    }
+
+    fn scopes(&self) -> Option<Vec<String>> {
+        self.scopes.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiKeyProvider;
+    use crate::client::Client;
+    use crate::requests::rates::get_rates;
+    use crate::test::server::TestServer;
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+
+    fn config(server: &TestServer) -> OAuthConfig {
+        let mut config = OAuthConfig::new(
+            "client",
+            "https://example.com/callback",
+            vec![String::from("offline_access"), String::from("partner.invoice.read")],
+        );
+        config.token_endpoint = server.url("/connect/token");
+        config
+    }
+
+    #[test]
+    fn test_pkce_challenge() {
+        //RFC 7636 appendix B
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        assert_eq!(pkce.challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+        assert_eq!(Pkce::new().verifier.len(), 64);
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let provider = OAuthProvider::new(OAuthConfig::new("client", "https://example.com/callback", vec![String::from("offline_access")]));
+        let pkce = Pkce::from_verifier("verifier");
+        let url = provider.authorization_url("xyz", &pkce).unwrap();
+        assert_eq!(
+            url,
+            format!(
+                "https://auth.strike.me/connect/authorize?response_type=code&client_id=client&redirect_uri=https%3A%2F%2Fexample.com%2Fcallback&scope=offline_access&state=xyz&code_challenge={}&code_challenge_method=S256",
+                pkce.challenge
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exchange_and_refresh() {
+        let server = TestServer::start();
+        //Expires inside the refresh margin, so the next access_token() refreshes
+        server.route(
+            "/connect/token",
+            200,
+            r#"{"access_token":"first","expires_in":30,"refresh_token":"refresh-1","scope":"offline_access partner.invoice.read","token_type":"Bearer"}"#,
+        );
+        let provider = OAuthProvider::new(config(&server));
+        assert_eq!(provider.scopes(), None);
+
+        let pkce = Pkce::from_verifier("verifier");
+        let tokens = provider.exchange_code("code-1", &pkce).await.unwrap();
+        assert_eq!(tokens.access_token, "first");
+        assert_eq!(provider.scopes(), Some(vec![String::from("offline_access"), String::from("partner.invoice.read")]));
+
+        let exchange = &server.requests()[0];
+        assert_eq!(exchange.method, "POST");
+        assert_eq!(
+            exchange.body,
+            "grant_type=authorization_code&code=code-1&redirect_uri=https%3A%2F%2Fexample.com%2Fcallback&code_verifier=verifier&client_id=client"
+        );
+
+        server.route("/connect/token", 200, r#"{"access_token":"second","expires_in":3600}"#);
+        assert_eq!(provider.access_token().await, Ok(String::from("second")));
+        assert_eq!(server.requests()[1].body, "grant_type=refresh_token&refresh_token=refresh-1&client_id=client");
+
+        //Still fresh, no further requests
+        assert_eq!(provider.access_token().await, Ok(String::from("second")));
+        assert_eq!(server.requests().len(), 2);
+        let tokens = provider.tokens().await.unwrap();
+        assert_eq!(tokens.refresh_token, Some(String::from("refresh-1")));
+        assert_eq!(tokens.scopes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_token_errors() {
+        let server = TestServer::start();
+        server.route("/connect/token", 400, r#"{"error":"invalid_grant","error_description":"code expired"}"#);
+        let provider = OAuthProvider::new(config(&server));
+
+        let tokens = provider.exchange_code("code-1", &Pkce::new()).await;
+        assert_eq!(tokens, Err(LNError::AuthError(String::from("invalid_grant: code expired"))));
+        assert!(matches!(provider.access_token().await, Err(LNError::AuthError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_bindings_use_client_provider() {
+        let mock = Arc::new(MockTransport::new());
+        let client = Client::new(mock.clone());
+        let mut config = OAuthConfig::new("client", "https://example.com/callback", vec![]);
+        config.token_endpoint = String::from("https://auth.strike.me/connect/token");
+        let provider = OAuthProvider::new(config);
+        mock.route(Method::Post, "/connect/token", 200, r#"{"access_token":"first","expires_in":30,"refresh_token":"refresh-1"}"#);
+        client.scope(provider.exchange_code("code-1", &Pkce::new())).await.unwrap();
+
+        //The api key is ignored and the expiring token refreshed first
+        mock.route(Method::Post, "/connect/token", 200, r#"{"access_token":"second","expires_in":3600}"#);
+        mock.route(Method::Get, "/v1/rates/ticker/", 200, "[]");
+        let client = client.with_auth(provider);
+        assert_eq!(client.scope(get_rates("api_key")).await, Ok(vec![]));
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].header("Authorization"), Some("Bearer second"));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_provider() {
+        let provider = ApiKeyProvider::from("api_key");
+        assert_eq!(provider.access_token().await, Ok(String::from("api_key")));
+        assert_eq!(provider.scopes(), None);
+    }
+}
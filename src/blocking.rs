@@ -0,0 +1,292 @@
+use crate::client::{Client, Timeouts};
+use crate::errors::LNError;
+use crate::transport::BlockingTransport;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+// Synchronous versions of the API for callers without an async runtime. Requests are sent
+// with a blocking reqwest client and the async functions are driven on the calling
+// thread, so these must not be called from inside an async runtime.
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Runs a future from this crate to completion on the calling thread. A client scoped
+// inside the future takes precedence over the blocking one.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let client = CLIENT.get_or_init(|| {
+        let transport = BlockingTransport::with_timeouts(&Timeouts::default()).expect("the default blocking reqwest client could not be built");
+        Client::new(transport)
+    });
+    let mut future = pin!(client.scope(future));
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+// Wraps `async fn name<'a, A: Into<Request<'a>>>(A) -> Result<Output, LNError>`
+macro_rules! blocking {
+    ($module:ident :: $name:ident, $request:ident, $output:ty) => {
+        pub fn $name<'a, A>(request: A) -> Result<$output, LNError>
+        where
+            A: Into<$request<'a>>,
+        {
+            block_on($module::$name(request))
+        }
+    };
+}
+
+pub mod rates {
+    use super::*;
+    use crate::requests::rates::{self, RatesRequest};
+    use crate::types::Rate;
+
+    blocking!(rates::get_rates, RatesRequest, Vec<Rate>);
+}
+
+pub mod accounts {
+    use super::*;
+    use crate::requests::accounts::{self, AccountHandleRequest, AccountIdRequest, CurrentAccountRequest};
+    use crate::types::Account;
+
+    blocking!(accounts::get_account_by_handle, AccountHandleRequest, Account);
+    blocking!(accounts::account_handle_exists, AccountHandleRequest, bool);
+    blocking!(accounts::get_account_by_id, AccountIdRequest, Account);
+    blocking!(accounts::get_current_account, CurrentAccountRequest, Account);
+}
+
+pub mod invoices {
+    use super::*;
+    use crate::cancel::CancellationToken;
+    use crate::requests::invoices::cancel::{self, CancelInvoiceRequest};
+    use crate::requests::invoices::find::{self, FindInvoiceRequest};
+    use crate::requests::invoices::get::{self, GetInvoicesRequest};
+    use crate::requests::invoices::issue::{self, InvoiceRequest};
+    use crate::types::{Invoice, Invoices};
+
+    blocking!(issue::issue_invoice, InvoiceRequest, Invoice);
+    blocking!(issue::issue_invoice_idempotent, InvoiceRequest, Invoice);
+    blocking!(find::find_invoice, FindInvoiceRequest, Invoice);
+    blocking!(cancel::cancel_invoice, CancelInvoiceRequest, ());
+    blocking!(get::get_invoices, GetInvoicesRequest, Invoices);
+
+    pub fn get_all_invoices<'a, A>(invoice_request: A, page_size: u32, cancel: &CancellationToken) -> Result<Vec<Invoice>, LNError>
+    where
+        A: Into<GetInvoicesRequest<'a>>,
+    {
+        block_on(get::get_all_invoices(invoice_request, page_size, cancel))
+    }
+}
+
+pub mod quote {
+    use super::*;
+    use crate::cancel::CancellationToken;
+    use crate::requests::quote::{self, QuoteRequest};
+    use crate::types::{Invoice, Quote};
+    use std::time::Duration;
+
+    blocking!(quote::request_quote, QuoteRequest, Quote);
+
+    pub fn refresh_quote_until_paid<'a, A, F>(
+        quote_request: A,
+        poll_interval: Duration,
+        on_quote: F,
+        cancel: &CancellationToken,
+    ) -> Result<Invoice, LNError>
+    where
+        A: Into<QuoteRequest<'a>>,
+        F: FnMut(&Quote),
+    {
+        block_on(quote::refresh_quote_until_paid(quote_request, poll_interval, on_quote, cancel))
+    }
+}
+
+pub mod subscriptions {
+    use super::*;
+    use crate::requests::subscriptions::create::{self, CreateSubscriptionRequest};
+    use crate::requests::subscriptions::get::{self, GetSubscriptionsRequest};
+    use crate::requests::subscriptions::subscription::SubscriptionsRequest;
+    use crate::requests::subscriptions::update::{self, UpdateSubscriptionRequest};
+    use crate::requests::subscriptions::{delete, find};
+    use crate::types::Subscription;
+
+    blocking!(create::subscribe, CreateSubscriptionRequest, Subscription);
+    blocking!(get::get_subscriptions, GetSubscriptionsRequest, Vec<Subscription>);
+    blocking!(find::find_subscription, SubscriptionsRequest, Subscription);
+    blocking!(update::update_subscription, UpdateSubscriptionRequest, Subscription);
+    blocking!(delete::delete_subscription, SubscriptionsRequest, ());
+}
+
+pub mod receive_requests {
+    use super::*;
+    use crate::requests::receive_requests::create::{self, CreateReceiveRequest};
+    use crate::requests::receive_requests::find::{self, FindReceiveRequest};
+    use crate::requests::receive_requests::receives::{self, GetReceivesRequest, ReceiveRequestReceivesRequest};
+    use crate::types::{ReceiveRequest, Receives};
+
+    blocking!(create::create_receive_request, CreateReceiveRequest, ReceiveRequest);
+    blocking!(find::find_receive_request, FindReceiveRequest, ReceiveRequest);
+    blocking!(receives::get_receives, GetReceivesRequest, Receives);
+    blocking!(receives::get_receive_request_receives, ReceiveRequestReceivesRequest, Receives);
+}
+
+pub mod payment_quotes {
+    use super::*;
+    use crate::requests::payment_quotes::execute::{self, ExecutePaymentQuoteRequest};
+    use crate::requests::payment_quotes::lightning::{self, LightningPaymentQuoteRequest};
+    use crate::requests::payment_quotes::lnurl::{self, LnurlPaymentQuoteRequest};
+    use crate::requests::payment_quotes::onchain::{self, OnchainPaymentQuoteRequest, OnchainTiersRequest};
+    use crate::types::{OnchainTier, Payment, PaymentQuote};
+
+    blocking!(lightning::create_lightning_payment_quote, LightningPaymentQuoteRequest, PaymentQuote);
+    blocking!(lnurl::create_lnurl_payment_quote, LnurlPaymentQuoteRequest, PaymentQuote);
+    blocking!(onchain::get_onchain_tiers, OnchainTiersRequest, Vec<OnchainTier>);
+    blocking!(onchain::create_onchain_payment_quote, OnchainPaymentQuoteRequest, PaymentQuote);
+    blocking!(execute::execute_payment_quote, ExecutePaymentQuoteRequest, Payment);
+}
+
+pub mod payments {
+    use super::*;
+    use crate::cancel::CancellationToken;
+    use crate::requests::payments::{self, FindPaymentRequest};
+    use crate::types::Payment;
+    use std::time::Duration;
+
+    blocking!(payments::find_payment, FindPaymentRequest, Payment);
+
+    pub fn wait_for_payment<'a, A>(
+        payment_request: A,
+        poll_interval: Duration,
+        max_attempts: u32,
+        cancel: &CancellationToken,
+    ) -> Result<Payment, LNError>
+    where
+        A: Into<FindPaymentRequest<'a>>,
+    {
+        block_on(payments::wait_for_payment(payment_request, poll_interval, max_attempts, cancel))
+    }
+}
+
+pub mod balances {
+    use super::*;
+    use crate::requests::balances::{self, BalancesRequest};
+    use crate::types::Balance;
+
+    blocking!(balances::get_balances, BalancesRequest, Vec<Balance>);
+}
+
+pub mod currency_exchange {
+    use super::*;
+    use crate::requests::currency_exchange::create::{self, CurrencyExchangeQuoteRequest};
+    use crate::requests::currency_exchange::execute::{self, ExecuteCurrencyExchangeQuoteRequest};
+    use crate::requests::currency_exchange::find::{self, FindCurrencyExchangeQuoteRequest};
+    use crate::cancel::CancellationToken;
+    use crate::types::CurrencyExchangeQuote;
+    use std::time::Duration;
+
+    blocking!(create::create_currency_exchange_quote, CurrencyExchangeQuoteRequest, CurrencyExchangeQuote);
+    blocking!(execute::execute_currency_exchange_quote, ExecuteCurrencyExchangeQuoteRequest, ());
+    blocking!(find::find_currency_exchange_quote, FindCurrencyExchangeQuoteRequest, CurrencyExchangeQuote);
+
+    pub fn wait_for_currency_exchange_quote<'a, A>(
+        find_request: A,
+        poll_interval: Duration,
+        max_attempts: u32,
+        cancel: &CancellationToken,
+    ) -> Result<CurrencyExchangeQuote, LNError>
+    where
+        A: Into<FindCurrencyExchangeQuoteRequest<'a>>,
+    {
+        block_on(find::wait_for_currency_exchange_quote(find_request, poll_interval, max_attempts, cancel))
+    }
+}
+
+pub mod lnurl {
+    use super::*;
+    use crate::lnurl::{self, LnurlPayRequest, PayRequestParams};
+
+    blocking!(lnurl::fetch_invoice, LnurlPayRequest, String);
+
+    pub fn resolve_pay_request(target: &str, scheme: &str) -> Result<PayRequestParams, LNError> {
+        block_on(lnurl::resolve_pay_request(target, scheme))
+    }
+}
+
+pub mod preflight {
+    use super::*;
+    use crate::requests::preflight::{self, Capabilities};
+
+    pub fn preflight(api_key: &str) -> Capabilities {
+        block_on(preflight::preflight(api_key))
+    }
+}
+
+#[cfg(feature = "tipping")]
+pub mod tipping {
+    use super::*;
+    use crate::tipping::{self, TippingRequest};
+    use crate::types::Quote;
+
+    blocking!(tipping::tipping_request, TippingRequest, Quote);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::server::TestServer;
+
+    #[test]
+    fn test_blocking_validation() {
+        match subscriptions::subscribe(("api_key", "http://example.com/hook", vec![])) {
+            Err(LNError::ValidationError(err)) => assert_eq!(err.fields(), vec!["webhook_url", "event_types"]),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_blocking_round_trip() {
+        let server = TestServer::start();
+        let params = serde_json::json!({
+            "callback": server.url("/lnurlp/alice/callback"),
+            "minSendable": 1_000,
+            "maxSendable": 100_000_000,
+            "metadata": "[]",
+            "tag": "payRequest",
+        });
+        server.route("/.well-known/lnurlp/alice", 200, &params.to_string());
+
+        let target = format!("alice@{}", server.address());
+        let params = lnurl::resolve_pay_request(&target, "http").unwrap();
+        assert_eq!(params.max_sendable, 100_000_000);
+        //The runtime is reused by later calls on the same thread
+        assert!(lnurl::resolve_pay_request(&target, "http").is_ok());
+    }
+
+    #[test]
+    fn test_blocking_cancellation() {
+        let cancel = crate::cancel::CancellationToken::new();
+        let canceller = cancel.clone();
+        let cancelling = std::thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(20));
+            canceller.cancel();
+        });
+        let waited = block_on(cancel.run(futures_timer::Delay::new(std::time::Duration::from_secs(5))));
+        assert_eq!(waited, Err(LNError::Cancelled));
+        cancelling.join().unwrap();
+    }
+}
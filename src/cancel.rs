@@ -0,0 +1,89 @@
+use crate::errors::LNError;
+use crate::time::race;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+// Stops long running helpers such as polling and pagination cleanly on shutdown. Clones
+// share one token, so cancelling any of them cancels every helper watching it.
+//
+//     let cancel = CancellationToken::new();
+//     let payment = wait_for_payment((api_key, payment_id), interval, 30, &cancel);
+//     ...
+//     cancel.cancel(); // payment resolves to Err(LNError::Cancelled)
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    // Resolves once the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            // Registered before checking so a cancel in between is not missed
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    // Runs future unless the token is cancelled first
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, LNError> {
+        if self.is_cancelled() {
+            return Err(LNError::Cancelled);
+        }
+        race(async { Ok(future.await) }, async {
+            self.cancelled().await;
+            Err(LNError::Cancelled)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_timer::Delay;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_stops_pending_future() {
+        let cancel = CancellationToken::new();
+        let watcher = cancel.clone();
+        let (result, _) = tokio::join!(watcher.run(Delay::new(Duration::from_secs(5))), async {
+            Delay::new(Duration::from_millis(10)).await;
+            cancel.cancel();
+        });
+        assert_eq!(result, Err(LNError::Cancelled));
+        assert!(watcher.is_cancelled());
+        assert_eq!(watcher.run(async { 1 }).await, Err(LNError::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_uncancelled_future_completes() {
+        assert_eq!(CancellationToken::new().run(async { 1 }).await, Ok(1));
+    }
+}
@@ -0,0 +1,572 @@
+use crate::auth::AuthProvider;
+use crate::dry_run::{placeholder_json, DryRunCall, DryRunLog};
+use crate::endpoint::{Endpoint, RawEndpoint};
+use crate::errors::LNError;
+use crate::redact::REDACTED;
+use crate::requests::request::{expected_body, expected_statuses, interpret};
+use crate::response;
+use crate::telemetry::{record_retry, send_observed};
+use crate::time::{timeout, Instant, SystemTime, UNIX_EPOCH};
+use crate::transport::{HttpRequest, HttpResponse, Method, ReqwestTransport, Transport};
+use futures_timer::Delay;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// A Client decides how requests are sent. The request functions in this crate take no
+// client argument, so a client is made current for the duration of a future with scope;
+// outside of any scope a shared reqwest client is used.
+//
+//     let client = Client::new(MockTransport::new());
+//     let rates = client.scope(get_rates(api_key)).await;
+//
+// Tasks spawned from inside a scope do not inherit it.
+//
+// Every request to Strike, from the bindings and from call, carries the client's default
+// headers unless it sets the same header itself. These start with a User-Agent naming this
+// crate. Requests to other hosts, such as LNURL services and token endpoints, and requests
+// passed to send directly, go out as they are.
+//
+// Timeouts apply per client, and per call by scoping a copy with other timeouts:
+//
+//     let quick = Client::current().with_timeouts(Timeouts::default().total(Duration::from_secs(5)));
+//     let invoice = quick.scope(find_invoice((api_key, invoice_id))).await;
+//
+// A client in dry run mode sends nothing, see the dry_run module.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    // Only applied by transports built with it, see ReqwestTransport::with_timeouts
+    pub connect: Option<Duration>,
+    // Across all attempts, including the waits between them
+    pub total: Option<Duration>,
+    pub attempt: Option<Duration>,
+    pub retries: u32,
+    // Doubled after each retry
+    pub backoff: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Timeouts {
+            connect: Some(Duration::from_secs(10)),
+            total: Some(Duration::from_secs(60)),
+            attempt: None,
+            retries: 0,
+            backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+impl Timeouts {
+    // No timeouts and no retries
+    pub fn none() -> Self {
+        Timeouts {
+            connect: None,
+            total: None,
+            attempt: None,
+            ..Timeouts::default()
+        }
+    }
+
+    pub fn connect(mut self, connect: Duration) -> Self {
+        self.connect = Some(connect);
+        self
+    }
+
+    pub fn total(mut self, total: Duration) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    pub fn attempt(mut self, attempt: Duration) -> Self {
+        self.attempt = Some(attempt);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+}
+
+pub const USER_AGENT: &str = concat!("strike-api/", env!("CARGO_PKG_VERSION"));
+pub const BASE_URL: &str = "https://api.strike.me/v1";
+
+#[derive(Clone)]
+pub struct Client {
+    transport: Arc<dyn Transport>,
+    timeouts: Timeouts,
+    headers: Vec<(String, String)>,
+    // Used by call and send_json, and by the bindings in this crate in place of their api_key
+    auth: Option<Arc<dyn AuthProvider>>,
+    base_url: String,
+    dry_run: Option<Arc<DryRunLog>>,
+}
+
+impl Client {
+    pub fn new<T: Transport + 'static>(transport: T) -> Self {
+        Client {
+            transport: Arc::new(transport),
+            timeouts: Timeouts::default(),
+            headers: vec![(String::from("User-Agent"), String::from(USER_AGENT))],
+            auth: None,
+            base_url: String::from(BASE_URL),
+            dry_run: None,
+        }
+    }
+
+    pub fn transport(&self) -> &Arc<dyn Transport> {
+        &self.transport
+    }
+
+    pub fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+
+    // A client sharing this one's transport with different timeouts
+    pub fn with_timeouts(&self, timeouts: Timeouts) -> Client {
+        Client {
+            timeouts,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_user_agent(&self, user_agent: &str) -> Client {
+        self.with_header("User-Agent", user_agent)
+    }
+
+    // Replaces any default header of the same name
+    pub fn with_header(&self, name: &str, value: &str) -> Client {
+        let mut client = self.clone();
+        client.headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        client.headers.push((name.to_string(), value.to_string()));
+        client
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn with_auth<A: AuthProvider + 'static>(&self, auth: A) -> Client {
+        Client {
+            auth: Some(Arc::new(auth)),
+            ..self.clone()
+        }
+    }
+
+    // For a sandbox or a proxy in front of the API, e.g. https://api.dev.strike.me/v1. Used by
+    // call and send_json, and by the bindings in place of https://api.strike.me/v1; bindings
+    // given another environment or api version keep their own URL.
+    pub fn with_base_url(&self, base_url: &str) -> Client {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            ..self.clone()
+        }
+    }
+
+    // A client sharing this one's settings that records requests instead of sending them,
+    // starting with an empty log
+    pub fn with_dry_run(&self) -> Client {
+        Client {
+            dry_run: Some(Arc::new(DryRunLog::default())),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    // Everything rendered so far by this client and the clients cloned from it
+    pub fn dry_run_calls(&self) -> Vec<DryRunCall> {
+        self.dry_run.as_ref().map(|log| log.calls()).unwrap_or_default()
+    }
+
+    // Points a URL of the default API at the client's base URL
+    pub(crate) fn rebase(&self, url: String) -> String {
+        match url.strip_prefix(BASE_URL) {
+            Some(rest) if rest.is_empty() || rest.starts_with(['/', '?']) => format!("{}{}", self.base_url, rest),
+            _ => url,
+        }
+    }
+
+    // Adds the default headers the request does not set itself
+    pub(crate) fn apply_headers(&self, request: &mut HttpRequest) {
+        for (name, value) in &self.headers {
+            if request.header(name).is_none() {
+                request.set_header(name.clone(), value.clone());
+            }
+        }
+    }
+
+    // The bearer credential of the auth provider, if the client has one. In dry run the
+    // provider is not asked, as a token refresh would reach the network, and the header is
+    // redacted in the log anyway.
+    pub(crate) async fn access_token(&self) -> Result<Option<String>, LNError> {
+        match (&self.auth, &self.dry_run) {
+            (None, _) => Ok(None),
+            (Some(_), Some(_)) => Ok(Some(REDACTED.to_string())),
+            (Some(auth), None) => auth.access_token().await.map(Some),
+        }
+    }
+
+    pub async fn call<E: Endpoint>(&self, endpoint: &E) -> Result<E::Response, LNError> {
+        endpoint.validate()?;
+        let token = self
+            .access_token()
+            .await?
+            .ok_or_else(|| LNError::AuthError(String::from("the client has no auth provider, see Client::with_auth")))?;
+        let method = endpoint.method();
+        let mut url = format!("{}/{}", self.base_url, endpoint.path().trim_start_matches('/'));
+        let query = endpoint.query();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&form_urlencoded::Serializer::new(String::new()).extend_pairs(query).finish());
+        }
+
+        let mut request = HttpRequest::new(method, url);
+        request.set_header("Content-Type", "application/json");
+        request.set_header("Accept", "application/json");
+        request.set_header("Authorization", format!("Bearer {}", token));
+        if let Some(key) = endpoint.idempotency_key() {
+            request.set_header("Idempotency-Key", key);
+        }
+        request.body = endpoint.body().map(|body| body.to_string());
+        self.apply_headers(&mut request);
+
+        let (statuses, body) = (endpoint.expected_statuses(), endpoint.expected_body());
+        let response = self.send_or_render(request, || placeholder_json::<E::Response>(statuses, body)).await?;
+        interpret(response, statuses, body)
+    }
+
+    pub async fn send_json(&self, method: Method, path: &str, body: Option<serde_json::Value>) -> Result<serde_json::Value, LNError> {
+        self.call(&RawEndpoint { method, path, body }).await
+    }
+
+    // In dry run the placeholder is the first status the method succeeds with and an empty
+    // object, or no body for a delete
+    pub async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let method = request.method;
+        self.send_or_render(request, || placeholder_json::<serde_json::Value>(expected_statuses(method), expected_body(method)))
+            .await
+    }
+
+    pub(crate) async fn send_or_render<P>(&self, request: HttpRequest, placeholder: P) -> Result<HttpResponse, LNError>
+    where
+        P: FnOnce() -> HttpResponse,
+    {
+        let response = match (&self.dry_run, self.timeouts.total) {
+            (Some(log), _) => log.record(request, placeholder()),
+            (None, Some(total)) => timeout(total, self.send_attempts(request)).await.and_then(|result| result)?,
+            (None, None) => self.send_attempts(request).await?,
+        };
+        response::record(&response);
+        Ok(response)
+    }
+
+    async fn send_attempts(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let started = Instant::now();
+        let mut retries = 0;
+        loop {
+            let attempt = send_observed(self.transport.as_ref(), request.clone());
+            let result = match self.timeouts.attempt {
+                Some(limit) => timeout(limit, attempt).await.and_then(|result| result),
+                None => attempt.await,
+            };
+            if retries == self.timeouts.retries || !is_retryable(&request, &result) {
+                return result;
+            }
+            let wait = match result.as_ref().ok().and_then(retry_after) {
+                Some(retry_after) => retry_after,
+                None => self.timeouts.backoff * 2u32.saturating_pow(retries),
+            };
+            // Waiting past the total timeout would only turn the response into a Timeout
+            if self.timeouts.total.is_some_and(|total| started.elapsed() + wait >= total) {
+                return result;
+            }
+            retries += 1;
+            record_retry(request.method, request.path());
+            Delay::new(wait).await;
+        }
+    }
+
+    // Runs future with this client as the current one
+    pub fn scope<F: Future>(&self, future: F) -> Scoped<F> {
+        Scoped {
+            client: self.clone(),
+            future: Box::pin(future),
+        }
+    }
+
+    // The client of the innermost scope being polled on this thread, or the default
+    pub fn current() -> Client {
+        CURRENT.with(|current| current.borrow().clone()).unwrap_or_else(default_client)
+    }
+
+    // Replaces the client used outside of any scope, for the whole process, and returns the
+    // one it replaces so it can be restored. Unlike a scope this also covers spawned tasks.
+    pub fn set_default(client: Client) -> Client {
+        let mut default = DEFAULT.write().unwrap();
+        let previous = default.take().unwrap_or_default();
+        *default = Some(client);
+        previous
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        // As reqwest::Client::new, panics if the TLS backend cannot be initialized
+        Client::new(ReqwestTransport::with_timeouts(&Timeouts::default()).expect("the default reqwest client could not be built"))
+    }
+}
+
+// Only requests that are safe to send twice are retried: reads, deletes and anything
+// carrying an idempotency key
+fn is_retryable(request: &HttpRequest, result: &Result<HttpResponse, LNError>) -> bool {
+    let replayable = matches!(request.method, Method::Get | Method::Delete) || request.header("Idempotency-Key").is_some();
+    replayable
+        && match result {
+            Ok(response) => matches!(response.status, 429 | 502 | 503 | 504),
+            Err(err) => matches!(err, LNError::HTTPError(_) | LNError::Timeout(_)),
+        }
+}
+
+// The wait a 429 or 503 asks for in Retry-After, as seconds or an HTTP date
+fn retry_after(response: &HttpResponse) -> Option<Duration> {
+    if !matches!(response.status, 429 | 503) {
+        return None;
+    }
+    let value = response.header("Retry-After")?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = httpdate::parse_http_date(value).ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    Some(at.saturating_sub(now))
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Client>> = const { RefCell::new(None) };
+}
+
+static DEFAULT: RwLock<Option<Client>> = RwLock::new(None);
+
+fn default_client() -> Client {
+    if let Some(client) = DEFAULT.read().unwrap().as_ref() {
+        return client.clone();
+    }
+    DEFAULT.write().unwrap().get_or_insert_with(Client::default).clone()
+}
+
+pub struct Scoped<F: Future> {
+    client: Client,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let previous = CURRENT.with(|current| current.replace(Some(self.client.clone())));
+        // Restores the outer scope even if the inner future panics
+        struct Restore(Option<Client>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+            }
+        }
+        let _restore = Restore(previous);
+        self.future.as_mut().poll(cx)
+    }
+}
+
+// Sends with the current client, or renders the placeholder in a dry run
+pub(crate) async fn send_or_render<P>(request: HttpRequest, placeholder: P) -> Result<HttpResponse, LNError>
+where
+    P: FnOnce() -> HttpResponse,
+{
+    Client::current().send_or_render(request, placeholder).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiKeyProvider;
+    use crate::endpoint::send_json;
+    use crate::requests::balances::{get_balances, BalancesRequest};
+    use crate::transport::MockTransport;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_scopes_nest_and_restore() {
+        let outer = Arc::new(MockTransport::new());
+        outer.route(Method::Get, "/outer", 200, "outer");
+        let inner = Arc::new(MockTransport::new());
+        inner.route(Method::Get, "/inner", 200, "inner");
+        let (outer_client, inner_client) = (Client::new(outer.clone()), Client::new(inner.clone()));
+
+        let bodies = outer_client
+            .scope(async {
+                let first = Client::current().send(HttpRequest::new(Method::Get, "http://test/outer")).await.unwrap().body;
+                let second = inner_client
+                    .scope(async { Client::current().send(HttpRequest::new(Method::Get, "http://test/inner")).await })
+                    .await
+                    .unwrap()
+                    .body;
+                let third = Client::current().send(HttpRequest::new(Method::Get, "http://test/outer")).await.unwrap().body;
+                vec![first, second, third]
+            })
+            .await;
+        assert_eq!(bodies, vec!["outer", "inner", "outer"]);
+        assert_eq!(outer.requests().len(), 2);
+        assert_eq!(inner.requests().len(), 1);
+    }
+
+    // Answers with each status in turn, then 200; a status of 0 hangs
+    struct Flaky {
+        statuses: Mutex<Vec<u16>>,
+        sent: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl Flaky {
+        fn new(statuses: &[u16]) -> Self {
+            Flaky {
+                statuses: Mutex::new(statuses.iter().rev().copied().collect()),
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Transport for Flaky {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+            self.sent.lock().unwrap().push(request);
+            let status = self.statuses.lock().unwrap().pop().unwrap_or(200);
+            if status == 0 {
+                Delay::new(Duration::from_secs(5)).await;
+            }
+            Ok(HttpResponse::new(status, "{}"))
+        }
+    }
+
+    fn retrying() -> Timeouts {
+        Timeouts::none().retries(2, Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_retries_idempotent_requests() {
+        let flaky = Arc::new(Flaky::new(&[503, 429]));
+        let client = Client::new(flaky.clone()).with_timeouts(retrying());
+        let response = client.send(HttpRequest::new(Method::Get, "http://test/v1/rates/ticker")).await;
+        assert_eq!(response.unwrap().status, 200);
+        assert_eq!(flaky.sent.lock().unwrap().len(), 3);
+
+        let flaky = Arc::new(Flaky::new(&[503, 503, 503]));
+        let client = Client::new(flaky.clone()).with_timeouts(retrying());
+        let mut post = HttpRequest::new(Method::Post, "http://test/v1/invoices");
+        post.set_header("Idempotency-Key", "order-1");
+        assert_eq!(client.send(post).await.unwrap().status, 503);
+        assert_eq!(flaky.sent.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_after_retry_after() {
+        let mock = Arc::new(MockTransport::new());
+        let mut limited = HttpResponse::new(429, "");
+        limited.headers.push((String::from("Retry-After"), String::from("0")));
+        mock.respond(Method::Get, "/v1/balances", limited.clone());
+
+        //Honoured in place of the ten second backoff
+        let client = Client::new(mock.clone()).with_timeouts(Timeouts::none().retries(2, Duration::from_secs(10)));
+        let request = HttpRequest::new(Method::Get, "http://test/v1/balances");
+        let response = timeout(Duration::from_secs(1), client.send(request.clone())).await.unwrap();
+        assert_eq!(response.unwrap().status, 429);
+        assert_eq!(mock.requests().len(), 3);
+
+        //A wait past the total timeout is not started
+        limited.headers[0].1 = String::from("60");
+        mock.respond(Method::Get, "/v1/balances", limited.clone());
+        let client = client.with_timeouts(Timeouts::none().total(Duration::from_secs(1)).retries(2, Duration::from_millis(1)));
+        assert_eq!(client.send(request).await.unwrap().status, 429);
+        assert_eq!(mock.requests().len(), 4);
+
+        limited.headers[0].1 = String::from("Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(retry_after(&limited), Some(Duration::ZERO));
+        assert_eq!(retry_after(&HttpResponse::new(502, "")), None);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_posts_without_idempotency_key() {
+        let flaky = Arc::new(Flaky::new(&[503]));
+        let client = Client::new(flaky.clone()).with_timeouts(retrying());
+        let response = client.send(HttpRequest::new(Method::Post, "http://test/v1/invoices")).await;
+        assert_eq!(response.unwrap().status, 503);
+        assert_eq!(flaky.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_attempt_and_total_timeouts() {
+        //The first attempt hangs and is abandoned, the retry succeeds
+        let flaky = Arc::new(Flaky::new(&[0]));
+        let client = Client::new(flaky.clone()).with_timeouts(retrying().attempt(Duration::from_millis(10)));
+        let response = client.send(HttpRequest::new(Method::Get, "http://test/v1/balances")).await;
+        assert_eq!(response.unwrap().status, 200);
+        assert_eq!(flaky.sent.lock().unwrap().len(), 2);
+
+        let client = Client::new(Flaky::new(&[0])).with_timeouts(Timeouts::none().total(Duration::from_millis(10)));
+        let response = client.send(HttpRequest::new(Method::Get, "http://test/v1/balances")).await;
+        assert!(matches!(response, Err(LNError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_default_headers() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Get, "/v1/balances", 200, "[]");
+        let client = Client::new(mock.clone())
+            .with_user_agent("checkout/2.1")
+            .with_header("X-Tenant", "acme");
+
+        client.scope(get_balances("api_key")).await.unwrap();
+        let sent = mock.last_request().unwrap();
+        assert_eq!(sent.header("User-Agent"), Some("checkout/2.1"));
+        assert_eq!(sent.header("X-Tenant"), Some("acme"));
+
+        let client = client.with_auth(ApiKeyProvider::new("api_key"));
+        let mut request = HttpRequest::new(Method::Get, "http://test/v1/balances");
+        request.set_header("x-tenant", "globex");
+        client.send(request).await.unwrap();
+        assert_eq!(mock.last_request().unwrap().header("X-Tenant"), Some("globex"));
+        assert_eq!(mock.last_request().unwrap().header("User-Agent"), None);
+        client.scope(send_json(Method::Get, "balances", None)).await.unwrap();
+        assert_eq!(mock.last_request().unwrap().header("X-Tenant"), Some("acme"));
+
+        Client::new(mock.clone()).scope(get_balances("api_key")).await.unwrap();
+        assert_eq!(mock.last_request().unwrap().header("user-agent"), Some(USER_AGENT));
+    }
+
+    #[tokio::test]
+    async fn test_base_url() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Get, "/v1/balances", 200, "[]");
+        let client = Client::new(mock.clone()).with_base_url("https://api.dev.strike.me/v1/");
+
+        client.scope(get_balances("api_key")).await.unwrap();
+        assert_eq!(mock.last_request().unwrap().url, "https://api.dev.strike.me/v1/balances");
+        let request = BalancesRequest {
+            api_key: "api_key",
+            environment: "proxy.example.com",
+            api_version: "v1",
+        };
+        client.scope(get_balances(request)).await.unwrap();
+        assert_eq!(mock.last_request().unwrap().url, "https://proxy.example.com/v1/balances");
+
+        assert_eq!(client.rebase(String::from("https://api.strike.me/v10/x")), "https://api.strike.me/v10/x");
+        assert_eq!(client.rebase(String::from("https://api.strike.me/v1")), "https://api.dev.strike.me/v1");
+    }
+}
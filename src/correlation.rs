@@ -0,0 +1,95 @@
+use rand::{thread_rng, RngCore};
+use crate::time::{SystemTime, UNIX_EPOCH};
+
+// Correlation ids make invoice issuing idempotent: the same id is sent on every retry and
+// an invoice that already carries it is returned instead of a duplicate being created.
+
+pub trait CorrelationIdGenerator: Send + Sync {
+    fn generate(&self) -> String;
+}
+
+impl<F> CorrelationIdGenerator for F
+where
+    F: Fn() -> String + Send + Sync,
+{
+    fn generate(&self) -> String {
+        self()
+    }
+}
+
+// Random RFC 4122 version 4 UUIDs, e.g. 3b241101-e2bb-4255-8caf-4136c566a962
+#[derive(Default, Debug, Clone, Copy)]
+pub struct UuidV4;
+
+impl CorrelationIdGenerator for UuidV4 {
+    fn generate(&self) -> String {
+        let mut bytes = [0u8; 16];
+        thread_rng().fill_bytes(&mut bytes);
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+    }
+}
+
+const CROCKFORD_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+// ULIDs sort by creation time, which keeps related invoices together when listed by id
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Ulid;
+
+impl CorrelationIdGenerator for Ulid {
+    fn generate(&self) -> String {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis())
+            .unwrap_or(0);
+        let mut random = [0u8; 10];
+        thread_rng().fill_bytes(&mut random);
+        encode_ulid(millis as u64, random)
+    }
+}
+
+// 48 bits of milliseconds followed by 80 random bits, as 26 Crockford base32 characters
+fn encode_ulid(millis: u64, random: [u8; 10]) -> String {
+    let mut value: u128 = ((millis & 0xffff_ffff_ffff) as u128) << 80;
+    for (i, byte) in random.iter().enumerate() {
+        value |= (*byte as u128) << (8 * (9 - i));
+    }
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD_ALPHABET[((value >> (5 * i)) & 31) as usize] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_v4() {
+        let id = UuidV4.generate();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert!("89ab".contains(&id[19..20]));
+        assert_ne!(id, UuidV4.generate());
+    }
+
+    #[test]
+    fn test_ulid() {
+        assert_eq!(encode_ulid(0, [0; 10]), "00000000000000000000000000");
+        assert_eq!(encode_ulid(1_469_918_176_385, [0; 10]), "01ARYZ6S410000000000000000");
+        assert_eq!(encode_ulid(0xffff_ffff_ffff, [0xff; 10]), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+
+        let id = Ulid.generate();
+        assert_eq!(id.len(), 26);
+        assert!(id.bytes().all(|c| CROCKFORD_ALPHABET.contains(&c)));
+    }
+
+    #[test]
+    fn test_closure_generator() {
+        let generator = || String::from("order-1");
+        assert_eq!(generator.generate(), "order-1");
+    }
+}
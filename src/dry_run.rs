@@ -0,0 +1,210 @@
+use crate::endpoint::BodyKind;
+use crate::redact::{redact_body, redact_headers};
+use crate::transport::{HttpRequest, HttpResponse};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Mutex;
+
+// A dry run client renders every request exactly as it would be sent, logs it and answers
+// with a placeholder instead of touching the network, so a batch job can be reviewed
+// before it runs for real.
+//
+//     let client = Client::current().with_dry_run();
+//     client.scope(issue_invoices(&orders)).await?;
+//     for call in client.dry_run_calls() {
+//         println!("{} {} {:?}", call.request.method, call.request.url, call.request.body);
+//     }
+//
+// Calls of this crate get the first of {}, [] and null their response type can be read
+// from as the placeholder body, with the first success status the endpoint expects.
+//
+// Like any scope, a dry run does not cover tasks spawned from inside it. Scope the spawned
+// future as well, or make the dry run client the default with Client::set_default.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunCall {
+    // With credentials in the headers and the body redacted
+    pub request: HttpRequest,
+    pub response: HttpResponse,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct DryRunLog {
+    calls: Mutex<Vec<DryRunCall>>,
+}
+
+impl DryRunLog {
+    pub(crate) fn record(&self, mut request: HttpRequest, response: HttpResponse) -> HttpResponse {
+        request.headers = redact_headers(&request.headers);
+        request.body = request.body.as_deref().map(redact_body);
+        self.calls.lock().unwrap().push(DryRunCall {
+            request,
+            response: response.clone(),
+        });
+        response
+    }
+
+    pub(crate) fn calls(&self) -> Vec<DryRunCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+// The first of {}, [] and null that T can be read from
+pub(crate) fn placeholder_json<T: DeserializeOwned>(statuses: &[u16], body: BodyKind) -> HttpResponse {
+    let body = match body {
+        BodyKind::Empty => "",
+        _ => ["{}", "[]", "null"]
+            .into_iter()
+            .find(|json| serde_json::from_str::<T>(json).is_ok())
+            .unwrap_or("{}"),
+    };
+    HttpResponse::new(first_status(statuses), body)
+}
+
+// A 200 with value as the body, for requests to hosts other than Strike
+pub(crate) fn placeholder_value<T: Serialize>(value: &T) -> HttpResponse {
+    HttpResponse::new(200, serde_json::to_string(value).unwrap_or_default())
+}
+
+fn first_status(statuses: &[u16]) -> u16 {
+    statuses.first().copied().unwrap_or(200)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::oauth::{OAuthConfig, OAuthProvider, Pkce};
+    use crate::auth::ApiKeyProvider;
+    use crate::client::Client;
+    use crate::endpoint::send_json;
+    use crate::lnurl::{fetch_invoice, LnurlPayRequest};
+    use crate::redact::REDACTED;
+    use crate::requests::invoices::issue::{issue_invoice, InvoiceRequest, InvoiceRequestData};
+    use crate::requests::rates::get_rates;
+    use crate::requests::subscriptions::create::subscribe;
+    use crate::requests::subscriptions::delete::delete_subscription;
+    use crate::transport::{Method, MockTransport};
+    use crate::types::{Amount, Event};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_dry_run_renders_without_sending() {
+        let mock = Arc::new(MockTransport::new());
+        let client = Client::new(mock.clone()).with_dry_run();
+        let request = InvoiceRequest {
+            api_key: "api_key",
+            account_handle: "magog",
+            invoice_request_data: InvoiceRequestData {
+                description: "order 1",
+                amount: Amount {
+                    amount: String::from("1.00"),
+                    currency: String::from("USD"),
+                },
+                correlation_id: "order-1",
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        };
+
+        let invoice = client.scope(issue_invoice(request)).await.unwrap();
+        assert_eq!(invoice.invoice_id, "");
+        client.scope(delete_subscription(("api_key", "s1"))).await.unwrap();
+        assert!(mock.requests().is_empty());
+
+        let calls = client.dry_run_calls();
+        assert_eq!(calls.len(), 2);
+        let issue = &calls[0];
+        assert_eq!(issue.request.method, Method::Post);
+        assert_eq!(issue.request.url, "https://api.strike.me/v1/invoices/handle/magog/");
+        assert_eq!(issue.request.header("Authorization"), Some(REDACTED));
+        assert_eq!(issue.request.header("Idempotency-Key"), Some("order-1"));
+        let body: Value = serde_json::from_str(issue.request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["correlationId"], "order-1");
+        assert_eq!((issue.response.status, &issue.response.body[..]), (201, "{}"));
+        assert_eq!(calls[1].request.method, Method::Delete);
+        assert_eq!((calls[1].response.status, &calls[1].response.body[..]), (204, ""));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_raw_calls() {
+        let client = Client::new(MockTransport::new())
+            .with_auth(ApiKeyProvider::new("api_key"))
+            .with_dry_run();
+        let body = json!({ "description": "batch" });
+        let response = client.scope(send_json(Method::Post, "/invoices", Some(body.clone()))).await;
+        assert_eq!(response, Ok(json!({})));
+
+        let call = &client.dry_run_calls()[0];
+        assert_eq!(call.request.url, "https://api.strike.me/v1/invoices");
+        assert_eq!(call.request.body, Some(body.to_string()));
+        assert_eq!(call.request.header("Authorization"), Some(REDACTED));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_redacts_bodies() {
+        let client = Client::new(MockTransport::new()).with_dry_run();
+        let subscription = subscribe(("api_key", "https://example.com/webhook", vec![Event::InvoiceCreated]));
+        let secret = client.scope(subscription).await.unwrap().secret;
+
+        let mut config = OAuthConfig::new("client", "https://example.com/callback", vec![]);
+        config.client_secret = Some(String::from("client-s3cret"));
+        let provider = OAuthProvider::new(config);
+        let pkce = Pkce::from_verifier("verifier-s3cret");
+        client.scope(provider.exchange_code("code-1", &pkce)).await.unwrap();
+
+        let calls = client.dry_run_calls();
+        let body: Value = serde_json::from_str(calls[0].request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["secret"], REDACTED);
+        assert_eq!(body["webhookUrl"], "https://example.com/webhook");
+        let form = calls[1].request.body.as_deref().unwrap();
+        assert!(form.contains("client_id=client"));
+        for leaked in [&secret[..], "client-s3cret", "verifier-s3cret", "code-1"] {
+            assert!(calls.iter().all(|call| !call.request.body.as_deref().unwrap().contains(leaked)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_lnurl_and_oauth() {
+        let client = Client::new(MockTransport::new()).with_dry_run();
+
+        let lnurl_pay_request = LnurlPayRequest::from(("alice@example.com", 1_000_000, "thanks!"));
+        let invoice = client.scope(fetch_invoice(lnurl_pay_request)).await;
+        assert_eq!(invoice, Ok(String::new()));
+
+        let config = OAuthConfig::new("client", "https://example.com/callback", vec![]);
+        let provider = OAuthProvider::new(config);
+        let tokens = client.scope(provider.exchange_code("code", &Pkce::from_verifier("verifier"))).await.unwrap();
+        assert_eq!(tokens.access_token, REDACTED);
+
+        let calls = client.dry_run_calls();
+        let urls: Vec<&str> = calls.iter().map(|call| &call.request.url[..]).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/.well-known/lnurlp/alice",
+                "https://example.com/.well-known/lnurlp/alice?amount=1000000&comment=thanks%21",
+                "https://auth.strike.me/connect/token",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_scoped_spawned_tasks() {
+        let mock = Arc::new(MockTransport::new());
+        let client = Client::new(mock.clone()).with_dry_run();
+        let rates = client
+            .scope(async { tokio::spawn(Client::current().scope(get_rates("api_key"))).await.unwrap() })
+            .await;
+        assert_eq!(rates, Ok(vec![]));
+        assert_eq!(client.dry_run_calls().len(), 1);
+        assert!(mock.requests().is_empty());
+    }
+
+    #[test]
+    fn test_placeholder_json() {
+        assert_eq!(placeholder_json::<Vec<u32>>(&[200], BodyKind::Json).body, "[]");
+        assert_eq!(placeholder_json::<Option<u32>>(&[200], BodyKind::Json).body, "null");
+        assert_eq!(placeholder_json::<Value>(&[201], BodyKind::Either).status, 201);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::client::Client;
+use crate::errors::LNError;
+use crate::requests::request::{expected_body, expected_statuses};
+use crate::transport::Method;
+use crate::validation::ValidationError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+// Calls Strike endpoints this crate has no binding for yet. Implement Endpoint for a typed
+// request, or use send_json for a one off; either way the request goes through the
+// current client, with its auth provider, headers, timeouts, retries and error mapping.
+//
+//     struct FindReceive<'a>(&'a str);
+//
+//     impl Endpoint for FindReceive<'_> {
+//         type Response = Receive;
+//         fn method(&self) -> Method { Method::Get }
+//         fn path(&self) -> String { format!("/receives/{}", self.0) }
+//     }
+//
+//     let client = Client::current().with_auth(ApiKeyProvider::new(api_key));
+//     let receive = client.call(&FindReceive(receive_id)).await?;
+//     let balances = client.send_json(Method::Get, "/balances", None).await?;
+
+// What a successful response carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyKind {
+    Json,
+    // Any body is ignored
+    Empty,
+    // JSON, or nothing, which is read as null
+    Either,
+}
+
+pub trait Endpoint {
+    // () for endpoints that answer without a body
+    type Response: DeserializeOwned;
+
+    fn method(&self) -> Method;
+
+    // Below the client's base url, e.g. /invoices/{id}/quote
+    fn path(&self) -> String;
+
+    fn query(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    fn body(&self) -> Option<Value> {
+        None
+    }
+
+    // Sent as Idempotency-Key, which also lets the client retry the request
+    fn idempotency_key(&self) -> Option<String> {
+        None
+    }
+
+    // Run before anything is sent
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    // Other statuses are errors, UnexpectedStatus if they are a success
+    fn expected_statuses(&self) -> &[u16] {
+        expected_statuses(self.method())
+    }
+
+    // As for the bindings, JSON except for deletes
+    fn expected_body(&self) -> BodyKind {
+        expected_body(self.method())
+    }
+}
+
+// Calls endpoint with the current client
+pub async fn call<E: Endpoint>(endpoint: &E) -> Result<E::Response, LNError> {
+    Client::current().call(endpoint).await
+}
+
+// Sends a JSON request with the current client, returning the response body as JSON
+pub async fn send_json(method: Method, path: &str, body: Option<Value>) -> Result<Value, LNError> {
+    Client::current().send_json(method, path, body).await
+}
+
+pub(crate) struct RawEndpoint<'a> {
+    pub method: Method,
+    pub path: &'a str,
+    pub body: Option<Value>,
+}
+
+impl Endpoint for RawEndpoint<'_> {
+    type Response = Value;
+
+    fn method(&self) -> Method {
+        self.method
+    }
+
+    fn path(&self) -> String {
+        self.path.to_string()
+    }
+
+    fn body(&self) -> Option<Value> {
+        self.body.clone()
+    }
+
+    // Whatever the endpoint answers with, an empty body being null
+    fn expected_body(&self) -> BodyKind {
+        BodyKind::Either
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiKeyProvider;
+    use crate::errors::ResponseError;
+    use crate::transport::MockTransport;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Receive {
+        receive_id: String,
+        state: String,
+    }
+
+    struct FindReceives<'a> {
+        receive_request_id: &'a str,
+    }
+
+    impl Endpoint for FindReceives<'_> {
+        type Response = Vec<Receive>;
+
+        fn method(&self) -> Method {
+            Method::Get
+        }
+
+        fn path(&self) -> String {
+            String::from("/receive-requests/receives")
+        }
+
+        fn query(&self) -> Vec<(String, String)> {
+            vec![(String::from("$filter"), format!("receiveRequestId eq {}", self.receive_request_id))]
+        }
+    }
+
+    struct Forget<'a>(&'a str);
+
+    impl Endpoint for Forget<'_> {
+        type Response = ();
+
+        fn method(&self) -> Method {
+            Method::Delete
+        }
+
+        fn path(&self) -> String {
+            format!("/subscriptions/{}", self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_endpoint() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(
+            Method::Get,
+            "/v1/receive-requests/receives",
+            200,
+            r#"[{"receiveId":"r1","state":"COMPLETED"}]"#,
+        );
+        mock.route(Method::Delete, "/v1/subscriptions/s1", 204, "");
+        let client = Client::new(mock.clone()).with_auth(ApiKeyProvider::new("api_key"));
+
+        let receives = client.scope(call(&FindReceives { receive_request_id: "rr1" })).await.unwrap();
+        assert_eq!(receives, vec![Receive { receive_id: String::from("r1"), state: String::from("COMPLETED") }]);
+        let sent = mock.last_request().unwrap();
+        assert_eq!(sent.url, "https://api.strike.me/v1/receive-requests/receives?%24filter=receiveRequestId+eq+rr1");
+        assert_eq!(sent.header("Authorization"), Some("Bearer api_key"));
+        assert_eq!(sent.body, None);
+
+        assert_eq!(client.call(&Forget("s1")).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_send_json() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Post, "/v1/invoices", 201, r#"{"invoiceId":"i1"}"#);
+        let client = Client::new(mock.clone()).with_auth(ApiKeyProvider::new("api_key"));
+
+        let body = json!({ "amount": { "amount": "1.00", "currency": "USD" } });
+        let invoice = client.scope(send_json(Method::Post, "invoices", Some(body.clone()))).await;
+        assert_eq!(invoice, Ok(json!({ "invoiceId": "i1" })));
+        assert_eq!(mock.last_request().unwrap().body, Some(body.to_string()));
+
+        mock.route(Method::Patch, "/v1/invoices/i1/cancel", 200, "");
+        let cancelled = client.send_json(Method::Patch, "invoices/i1/cancel", None).await;
+        assert_eq!(cancelled, Ok(Value::Null));
+
+        let missing = client.send_json(Method::Get, "/invoices/unknown", None).await;
+        assert!(matches!(missing, Err(LNError::HTTPError(_))));
+
+        mock.route(Method::Get, "/v1/invoices/gone", 404, "not found");
+        let gone = client.send_json(Method::Get, "/invoices/gone", None).await;
+        assert_eq!(gone, Err(LNError::HTTPResponseError(ResponseError { status: 404, err: String::from("not found") })));
+    }
+
+    #[tokio::test]
+    async fn test_requires_auth() {
+        let client = Client::new(MockTransport::new());
+        let result = client.send_json(Method::Get, "/balances", None).await;
+        assert!(matches!(result, Err(LNError::AuthError(_))));
+    }
+}
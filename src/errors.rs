@@ -1,3 +1,5 @@
+use crate::redact::redact_body;
+use crate::validation::ValidationError;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Display, Formatter};
 
@@ -13,13 +15,69 @@ impl Display for ResponseError {
     }
 }
 
+// A 403 caused by the credential lacking a scope, rather than by the request itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissingScopeError {
+    pub scope: Option<String>,
+    pub err: String,
+}
+
+impl Display for MissingScopeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.scope {
+            Some(scope) => write!(f, "missing scope {}: {}", scope, self.err),
+            None => write!(f, "missing scope: {}", self.err),
+        }
+    }
+}
+
+// A body that could not be decoded, kept whole for diagnosis. Debug output redacts any
+// credentials in it; the body field itself is exactly what was received.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonError {
+    pub err: String,
+    pub body: String,
+}
+
+impl JsonError {
+    pub fn new(err: serde_json::Error, body: &str) -> Self {
+        JsonError {
+            err: err.to_string(),
+            body: body.to_string(),
+        }
+    }
+}
+
+impl Debug for JsonError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("JsonError")
+            .field("err", &self.err)
+            .field("body", &redact_body(&self.body))
+            .finish()
+    }
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} in {}", self.err, redact_body(&self.body))
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LNError {
     HTTPError(String),
     HTTPResponseError(ResponseError),
     StrikeError(String),
-    JsonError(String),
+    JsonError(JsonError),
+    InvalidAddress(String),
+    LnurlError(String),
+    AuthError(String),
+    MissingScope(MissingScopeError),
+    ValidationError(ValidationError),
+    // A success status the endpoint does not answer with, so the body may not be what was asked for
+    UnexpectedStatus(ResponseError),
+    Timeout(String),
+    Cancelled,
 }
 
 impl Display for LNError {
@@ -28,8 +86,20 @@ impl Display for LNError {
     }
 }
 
+impl std::error::Error for LNError {}
+
 impl From<reqwest::Error> for LNError {
     fn from(err: reqwest::Error) -> Self {
         LNError::HTTPError(err.to_string())
     }
-}
\ No newline at end of file
+}
+impl From<JsonError> for LNError {
+    fn from(err: JsonError) -> Self {
+        LNError::JsonError(err)
+    }
+}
+impl From<ValidationError> for LNError {
+    fn from(err: ValidationError) -> Self {
+        LNError::ValidationError(err)
+    }
+}
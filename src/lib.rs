@@ -6,5 +6,35 @@ pub mod tipping;
 pub mod types;
 #[cfg(feature = "base")]
 pub mod requests;
+#[cfg(feature = "base")]
+pub mod address;
+#[cfg(feature = "base")]
+pub mod lnurl;
+#[cfg(feature = "base")]
+pub mod auth;
+#[cfg(feature = "base")]
+pub mod validation;
+#[cfg(feature = "base")]
+pub mod correlation;
+#[cfg(feature = "base")]
+pub mod transport;
+#[cfg(feature = "base")]
+pub mod client;
+#[cfg(feature = "base")]
+pub mod endpoint;
+#[cfg(feature = "base")]
+pub mod response;
+#[cfg(feature = "base")]
+pub mod dry_run;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+#[cfg(feature = "base")]
+pub mod time;
+#[cfg(feature = "base")]
+pub mod cancel;
+#[cfg(feature = "base")]
+pub mod telemetry;
+#[cfg(feature = "types")]
+pub mod redact;
 #[cfg(test)]
 pub mod test;
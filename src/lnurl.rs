@@ -0,0 +1,390 @@
+use crate::address::{bech32_decode, convert_bits};
+use crate::errors::{JsonError, LNError};
+use reqwest::Url;
+use crate::client::{send_or_render, Client};
+use crate::dry_run::placeholder_value;
+use crate::transport::{HttpRequest, Method};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+// Client side LNURL-pay (LUD-06, LUD-16) so that payments can go to any Lightning Address,
+// independently of Strike's own LNURL quote endpoint. The BOLT11 returned by the payee is
+// checked against the advertised metadata and the requested amount before it is handed back.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightningAddress {
+    pub name: String,
+    pub domain: String,
+}
+
+impl LightningAddress {
+    pub fn well_known_url(&self, scheme: &str) -> String {
+        format!("{}://{}/.well-known/lnurlp/{}", scheme, self.domain, self.name)
+    }
+}
+
+impl FromStr for LightningAddress {
+    type Err = LNError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        let invalid = || LNError::LnurlError(format!("invalid Lightning Address: {}", address));
+        let (name, domain) = address.trim().split_once('@').ok_or_else(invalid)?;
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.+".contains(c));
+        if !valid_name || domain.is_empty() || domain.contains(['/', '@', ' ']) {
+            return Err(invalid());
+        }
+        Ok(LightningAddress {
+            name: name.to_string(),
+            domain: domain.to_ascii_lowercase(),
+        })
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayRequestParams {
+    pub callback: String,
+    pub min_sendable: u64,
+    pub max_sendable: u64,
+    pub metadata: String,
+    pub tag: String,
+    #[serde(default)]
+    pub comment_allowed: u32,
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+struct PayRequestCallback {
+    pr: String,
+}
+
+pub struct LnurlPayRequest<'a> {
+    // A Lightning Address (name@domain) or a bech32 encoded lnurl
+    pub target: &'a str,
+    pub amount_msat: u64,
+    pub comment: &'a str,
+    // Used to reach a Lightning Address domain; only tests should need anything but https
+    pub scheme: &'a str,
+}
+
+impl<'a> From<(&'a str, u64)> for LnurlPayRequest<'a> {
+    fn from((target, amount_msat): (&'a str, u64)) -> Self {
+        LnurlPayRequest {
+            target,
+            amount_msat,
+            comment: "",
+            scheme: "https",
+        }
+    }
+}
+
+impl<'a> From<(&'a str, u64, &'a str)> for LnurlPayRequest<'a> {
+    fn from((target, amount_msat, comment): (&'a str, u64, &'a str)) -> Self {
+        LnurlPayRequest {
+            target,
+            amount_msat,
+            comment,
+            scheme: "https",
+        }
+    }
+}
+
+// Converts a bech32 lnurl (LUD-01) to the URL it encodes
+pub fn decode_lnurl(lnurl: &str) -> Result<String, LNError> {
+    let invalid = || LNError::LnurlError(format!("invalid lnurl: {}", lnurl));
+    let lnurl = lnurl.trim();
+    let lnurl = lnurl.strip_prefix("lightning:").unwrap_or(lnurl);
+    let (hrp, data, _) = bech32_decode(lnurl).ok_or_else(invalid)?;
+    if hrp != "lnurl" {
+        return Err(invalid());
+    }
+    let bytes = convert_bits(&data, 5, 8, false).ok_or_else(invalid)?;
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+pub async fn resolve_pay_request(target: &str, scheme: &str) -> Result<PayRequestParams, LNError> {
+    let url = if target.contains('@') {
+        target.parse::<LightningAddress>()?.well_known_url(scheme)
+    } else {
+        decode_lnurl(target)?
+    };
+    let url = Url::parse(&url).map_err(|e| LNError::LnurlError(e.to_string()))?;
+    //A dry run is answered with a payRequest for any amount, paid through the same url
+    let placeholder = PayRequestParams {
+        callback: url.to_string(),
+        min_sendable: 0,
+        max_sendable: u64::MAX,
+        metadata: String::from("[]"),
+        tag: String::from("payRequest"),
+        comment_allowed: u32::MAX,
+    };
+    let params = get_lnurl_json(url, placeholder).await?;
+    if params.tag != "payRequest" {
+        return Err(LNError::LnurlError(format!("expected a payRequest, got {}", params.tag)));
+    }
+    if params.min_sendable > params.max_sendable {
+        return Err(LNError::LnurlError(String::from("minSendable is greater than maxSendable")));
+    }
+    Ok(params)
+}
+
+// Resolves the target and asks its callback for a BOLT11 for amount_msat
+pub async fn fetch_invoice<'a, A>(lnurl_pay_request: A) -> Result<String, LNError>
+where
+    A: Into<LnurlPayRequest<'a>>,
+{
+    let lnurl_pay_request = lnurl_pay_request.into();
+    let params = resolve_pay_request(lnurl_pay_request.target, lnurl_pay_request.scheme).await?;
+    let amount_msat = lnurl_pay_request.amount_msat;
+
+    if amount_msat < params.min_sendable || amount_msat > params.max_sendable {
+        return Err(LNError::LnurlError(format!(
+            "amount {} msat is outside of {}..={} msat",
+            amount_msat, params.min_sendable, params.max_sendable
+        )));
+    }
+    if lnurl_pay_request.comment.chars().count() > params.comment_allowed as usize {
+        return Err(LNError::LnurlError(format!(
+            "comment is longer than the {} characters allowed",
+            params.comment_allowed
+        )));
+    }
+
+    let mut callback = Url::parse(&params.callback).map_err(|e| LNError::LnurlError(e.to_string()))?;
+    callback
+        .query_pairs_mut()
+        .append_pair("amount", &amount_msat.to_string());
+    if !lnurl_pay_request.comment.is_empty() {
+        callback
+            .query_pairs_mut()
+            .append_pair("comment", lnurl_pay_request.comment);
+    }
+    let invoice = get_lnurl_json(callback, PayRequestCallback::default()).await?.pr;
+    //The placeholder invoice of a dry run has nothing to check
+    if Client::current().is_dry_run() {
+        return Ok(invoice);
+    }
+
+    let details = decode_bolt11(&invoice)?;
+    let metadata_hash = Sha256::digest(params.metadata.as_bytes()).to_vec();
+    if details.description_hash != Some(metadata_hash) {
+        return Err(LNError::LnurlError(String::from(
+            "invoice description hash does not match the metadata",
+        )));
+    }
+    if details.amount_msat != Some(amount_msat) {
+        return Err(LNError::LnurlError(String::from(
+            "invoice amount does not match the requested amount",
+        )));
+    }
+    Ok(invoice)
+}
+
+async fn get_lnurl_json<T: DeserializeOwned + Serialize>(url: Url, placeholder: T) -> Result<T, LNError> {
+    let mut request = HttpRequest::new(Method::Get, url.to_string());
+    request.set_header("Accept", "application/json");
+    let response = send_or_render(request, || placeholder_value(&placeholder)).await?;
+    let value: Value = serde_json::from_str(&response.body).map_err(|err| JsonError::new(err, &response.body))?;
+
+    //LNURL services report errors in the body, sometimes with a 200
+    if value.get("status").and_then(Value::as_str) == Some("ERROR") {
+        let reason = value.get("reason").and_then(Value::as_str).unwrap_or("unknown error");
+        return Err(LNError::LnurlError(reason.to_string()));
+    }
+    if !response.is_success() {
+        return Err(LNError::LnurlError(format!("unexpected status {}", response.status)));
+    }
+    serde_json::from_value(value).map_err(|err| LNError::from(JsonError::new(err, &response.body)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Bolt11Details {
+    amount_msat: Option<u64>,
+    description_hash: Option<Vec<u8>>,
+}
+
+// Reads just what LNURL-pay needs from a BOLT11. The signature is not verified.
+fn decode_bolt11(invoice: &str) -> Result<Bolt11Details, LNError> {
+    let invalid = |reason: &str| LNError::LnurlError(format!("invalid invoice: {}", reason));
+    let invoice = invoice.trim();
+    let invoice = invoice.strip_prefix("lightning:").unwrap_or(invoice);
+    let (hrp, data, _) = bech32_decode(invoice).ok_or_else(|| invalid("bad bech32"))?;
+
+    let hrp = hrp.strip_prefix("ln").ok_or_else(|| invalid("missing ln prefix"))?;
+    let amount = hrp.trim_start_matches(|c: char| c.is_ascii_lowercase());
+    let amount_msat = if amount.is_empty() {
+        None
+    } else {
+        let (digits, multiplier) = match amount.char_indices().last() {
+            Some((i, c)) if c.is_ascii_lowercase() => (&amount[..i], Some(c)),
+            _ => (amount, None),
+        };
+        let value = digits.parse::<u64>().map_err(|_| invalid("bad amount"))?;
+        let msat = match multiplier {
+            None => value.checked_mul(100_000_000_000),
+            Some('m') => value.checked_mul(100_000_000),
+            Some('u') => value.checked_mul(100_000),
+            Some('n') => value.checked_mul(100),
+            Some('p') if value % 10 == 0 => Some(value / 10),
+            _ => None,
+        };
+        Some(msat.ok_or_else(|| invalid("bad amount"))?)
+    };
+
+    //7 words of timestamp, then tagged fields, then a 104 word signature
+    if data.len() < 7 + 104 {
+        return Err(invalid("too short"));
+    }
+    let fields = &data[7..data.len() - 104];
+    let mut description_hash = None;
+    let mut i = 0;
+    while i + 3 <= fields.len() {
+        let tag = fields[i];
+        let length = (fields[i + 1] as usize) * 32 + fields[i + 2] as usize;
+        let field = fields.get(i + 3..i + 3 + length).ok_or_else(|| invalid("truncated field"))?;
+        //'h' is the description hash
+        if tag == 23 && length == 52 {
+            description_hash = convert_bits(field, 5, 8, false);
+        }
+        i += 3 + length;
+    }
+
+    Ok(Bolt11Details {
+        amount_msat,
+        description_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::bech32_encode;
+    use crate::client::{Client, USER_AGENT};
+    use crate::test::server::TestServer;
+
+    const METADATA: &str = r#"[["text/plain","Tip alice"],["text/identifier","alice@example.com"]]"#;
+
+    fn build_invoice(amount: &str, metadata: &str) -> String {
+        let mut data = vec![0u8; 7];
+        let hash = convert_bits(&Sha256::digest(metadata.as_bytes()), 8, 5, true).unwrap();
+        data.extend([23, (hash.len() / 32) as u8, (hash.len() % 32) as u8]);
+        data.extend(hash);
+        data.extend(vec![0u8; 104]);
+        bech32_encode(&format!("lnbc{}", amount), &data)
+    }
+
+    fn pay_request_server(invoice: &str) -> TestServer {
+        let server = TestServer::start();
+        let params = serde_json::json!({
+            "callback": server.url("/lnurlp/alice/callback"),
+            "minSendable": 1_000,
+            "maxSendable": 100_000_000,
+            "metadata": METADATA,
+            "tag": "payRequest",
+            "commentAllowed": 32,
+        });
+        server.route("/.well-known/lnurlp/alice", 200, &params.to_string());
+        server.route("/lnurlp/alice/callback", 200, &serde_json::json!({ "pr": invoice, "routes": [] }).to_string());
+        server
+    }
+
+    #[test]
+    fn test_parse_lightning_address() {
+        let address: LightningAddress = "alice@Example.com".parse().unwrap();
+        assert_eq!(address.name, "alice");
+        assert_eq!(address.well_known_url("https"), "https://example.com/.well-known/lnurlp/alice");
+        assert!("alice".parse::<LightningAddress>().is_err());
+        assert!("Alice@example.com".parse::<LightningAddress>().is_err());
+        assert!("alice@".parse::<LightningAddress>().is_err());
+    }
+
+    #[test]
+    fn test_decode_lnurl() {
+        //LUD-01 example
+        let url = decode_lnurl("LNURL1DP68GURN8GHJ7UM9WFMXJCM99E3K7MF0V9CXJ0M385EKVCENXC6R2C35XVUKXEFCV5MKVV34X5EKZD3EV56NYD3HXQURZEPEXEJXXEPNXSCRVWFNV9NXZCN9XQ6XYEFHVGCXXCMYXYMNSERXFQ5FNS").unwrap();
+        assert_eq!(url, "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df");
+    }
+
+    #[test]
+    fn test_decode_bolt11() {
+        let details = decode_bolt11(&build_invoice("10u", METADATA)).unwrap();
+        assert_eq!(details.amount_msat, Some(1_000_000));
+        assert_eq!(details.description_hash, Some(Sha256::digest(METADATA.as_bytes()).to_vec()));
+
+        let details = decode_bolt11(&build_invoice("", METADATA)).unwrap();
+        assert_eq!(details.amount_msat, None);
+
+        assert!(decode_bolt11("lnbc10u1qqqq").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_invoice() {
+        let invoice = build_invoice("10u", METADATA);
+        let server = pay_request_server(&invoice);
+        let target = format!("alice@{}", server.address());
+
+        let mut lnurl_pay_request = LnurlPayRequest::from((&target[..], 1_000_000, "thanks!"));
+        lnurl_pay_request.scheme = "http";
+        let fetched = fetch_invoice(lnurl_pay_request).await;
+        assert_eq!(fetched, Ok(invoice));
+
+        let callback = &server.requests()[1];
+        assert_eq!(callback.path, "/lnurlp/alice/callback?amount=1000000&comment=thanks%21");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_invoice_without_default_headers() {
+        let server = pay_request_server(&build_invoice("10u", METADATA));
+        let target = format!("alice@{}", server.address());
+        let client = Client::default().with_header("X-Tenant", "acme");
+
+        let mut lnurl_pay_request = LnurlPayRequest::from((&target[..], 1_000_000));
+        lnurl_pay_request.scheme = "http";
+        assert!(client.scope(fetch_invoice(lnurl_pay_request)).await.is_ok());
+        for request in server.requests() {
+            assert_eq!(request.headers.get("x-tenant"), None);
+            assert_ne!(request.headers.get("user-agent").map(String::as_str), Some(USER_AGENT));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_invoice_rejects_bad_invoices() {
+        let target_for = |server: &TestServer| format!("alice@{}", server.address());
+
+        //Description hash does not commit to the metadata
+        let server = pay_request_server(&build_invoice("10u", "other metadata"));
+        let target = target_for(&server);
+        let mut lnurl_pay_request = LnurlPayRequest::from((&target[..], 1_000_000));
+        lnurl_pay_request.scheme = "http";
+        assert!(matches!(fetch_invoice(lnurl_pay_request).await, Err(LNError::LnurlError(_))));
+
+        //Amount differs from the one requested
+        let server = pay_request_server(&build_invoice("20u", METADATA));
+        let target = target_for(&server);
+        let mut lnurl_pay_request = LnurlPayRequest::from((&target[..], 1_000_000));
+        lnurl_pay_request.scheme = "http";
+        assert!(matches!(fetch_invoice(lnurl_pay_request).await, Err(LNError::LnurlError(_))));
+
+        //Amount outside of min/max sendable never reaches the callback
+        let server = pay_request_server(&build_invoice("10u", METADATA));
+        let target = target_for(&server);
+        let mut lnurl_pay_request = LnurlPayRequest::from((&target[..], 500));
+        lnurl_pay_request.scheme = "http";
+        assert!(matches!(fetch_invoice(lnurl_pay_request).await, Err(LNError::LnurlError(_))));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lnurl_error_status() {
+        let server = TestServer::start();
+        server.route("/.well-known/lnurlp/alice", 200, r#"{"status":"ERROR","reason":"unknown user"}"#);
+        let target = format!("alice@{}", server.address());
+        let params = resolve_pay_request(&target, "http").await;
+        assert_eq!(params, Err(LNError::LnurlError(String::from("unknown user"))));
+    }
+}
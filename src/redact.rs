@@ -0,0 +1,105 @@
+use serde_json::Value;
+
+// Credentials never appear in Debug output or diagnostics. Headers and body fields that
+// carry them are replaced by REDACTED wherever requests are printed or traced.
+
+pub const REDACTED: &str = "<redacted>";
+
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+const SENSITIVE_FIELDS: &[&str] = &[
+    "secret",
+    "client_secret",
+    "access_token",
+    "refresh_token",
+    "code",
+    "code_verifier",
+    "apiKey",
+    "api_key",
+];
+
+pub fn is_sensitive_header(name: &str) -> bool {
+    SENSITIVE_HEADERS.iter().any(|sensitive| sensitive.eq_ignore_ascii_case(name))
+}
+
+pub fn is_sensitive_field(name: &str) -> bool {
+    SENSITIVE_FIELDS.iter().any(|sensitive| sensitive.eq_ignore_ascii_case(name))
+}
+
+pub fn redact_headers(headers: &[(String, String)]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| match is_sensitive_header(name) {
+            true => (name.clone(), REDACTED.to_string()),
+            false => (name.clone(), value.clone()),
+        })
+        .collect()
+}
+
+// Redacts sensitive fields of a JSON or form encoded body; anything else is kept as is
+pub fn redact_body(body: &str) -> String {
+    if let Ok(mut value) = serde_json::from_str::<Value>(body) {
+        redact_value(&mut value);
+        return value.to_string();
+    }
+    if body.contains('=') && !body.contains(char::is_whitespace) {
+        return body
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if is_sensitive_field(name) => format!("{}={}", name, REDACTED),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join("&");
+    }
+    body.to_string()
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if is_sensitive_field(name) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_headers() {
+        let headers = vec![
+            (String::from("Authorization"), String::from("Bearer key")),
+            (String::from("Accept"), String::from("application/json")),
+        ];
+        assert_eq!(
+            redact_headers(&headers),
+            vec![
+                (String::from("Authorization"), String::from(REDACTED)),
+                (String::from("Accept"), String::from("application/json")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redact_body() {
+        assert_eq!(
+            redact_body(r#"{"webhookUrl":"https://example.com","secret":"s3cret","nested":[{"secret":"x"}]}"#),
+            r#"{"nested":[{"secret":"<redacted>"}],"secret":"<redacted>","webhookUrl":"https://example.com"}"#
+        );
+        assert_eq!(
+            redact_body("grant_type=refresh_token&refresh_token=abc&client_id=id"),
+            "grant_type=refresh_token&refresh_token=<redacted>&client_id=id"
+        );
+        assert_eq!(redact_body("plain text"), "plain text");
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::time::Instant;
+
+use crate::errors::{LNError};
+use crate::types::{Account, Invoice};
+use crate::requests::accounts::{get_account_by_handle, get_account_by_id};
+
+// In-memory cache of account profiles, so that screens listing many invoices can resolve
+// issuer_id and receiver_id without a request per row. Entries expire after the ttl.
+pub struct AccountCache {
+    ttl: Duration,
+    by_id: Mutex<HashMap<String, (Instant, Account)>>,
+    by_handle: Mutex<HashMap<String, (Instant, Account)>>,
+}
+
+impl AccountCache {
+    pub fn new(ttl: Duration) -> Self {
+        AccountCache {
+            ttl,
+            by_id: Mutex::new(HashMap::new()),
+            by_handle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_by_id(&self, api_key: &str, account_id: &str) -> Result<Account, LNError> {
+        if let Some(account) = self.fresh(&self.by_id, account_id) {
+            return Ok(account);
+        }
+        let account = get_account_by_id((api_key, account_id)).await?;
+        self.store(&account, Some(account_id));
+        Ok(account)
+    }
+
+    pub async fn get_by_handle(&self, api_key: &str, handle: &str) -> Result<Account, LNError> {
+        if let Some(account) = self.fresh(&self.by_handle, handle) {
+            return Ok(account);
+        }
+        let account = get_account_by_handle((api_key, handle)).await?;
+        self.store(&account, None);
+        Ok(account)
+    }
+
+    // (issuer, receiver) of an invoice
+    pub async fn get_invoice_accounts(&self, api_key: &str, invoice: &Invoice) -> Result<(Account, Account), LNError> {
+        let issuer = self.get_by_id(api_key, &invoice.issuer_id).await?;
+        let receiver = self.get_by_id(api_key, &invoice.receiver_id).await?;
+        Ok((issuer, receiver))
+    }
+
+    pub fn invalidate(&self) {
+        self.by_id.lock().unwrap().clear();
+        self.by_handle.lock().unwrap().clear();
+    }
+
+    fn fresh(&self, entries: &Mutex<HashMap<String, (Instant, Account)>>, key: &str) -> Option<Account> {
+        let mut entries = entries.lock().unwrap();
+        match entries.get(key) {
+            Some((stored, account)) if stored.elapsed() < self.ttl => Some(account.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store(&self, account: &Account, account_id: Option<&str>) {
+        let now = Instant::now();
+        if let Some(account_id) = account.id.as_deref().or(account_id) {
+            self.by_id.lock().unwrap().insert(account_id.to_string(), (now, account.clone()));
+        }
+        self.by_handle.lock().unwrap().insert(account.handle.clone(), (now, account.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_accounts_expire() {
+        let account = Account {
+            id: Some("bf909224".to_string()),
+            handle: "magog".to_string(),
+            ..Default::default()
+        };
+
+        let cache = AccountCache::new(Duration::from_secs(60));
+        cache.store(&account, None);
+        assert_eq!(cache.fresh(&cache.by_id, "bf909224"), Some(account.clone()));
+        assert_eq!(cache.fresh(&cache.by_handle, "magog"), Some(account.clone()));
+        cache.invalidate();
+        assert_eq!(cache.fresh(&cache.by_handle, "magog"), None);
+
+        let cache = AccountCache::new(Duration::from_secs(0));
+        cache.store(&account, None);
+        assert_eq!(cache.fresh(&cache.by_id, "bf909224"), None);
+        assert!(cache.by_id.lock().unwrap().is_empty());
+    }
+}
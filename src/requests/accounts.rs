@@ -3,6 +3,7 @@ use crate::errors::{LNError};
 use crate::types::{Account};
 
 use crate::requests::request::{Requestable};
+use crate::validation::{ValidationError, Validator};
 
 pub struct AccountHandleRequest<'a> {
     pub api_key: &'a str,
@@ -23,13 +24,17 @@ impl<'a> Requestable for AccountHandleRequest<'a> {
             self.environment, self.api_version, self.handle
         )
     }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new().not_empty("handle", self.handle).finish()
+    }
 }
 
 impl<'a> From<(&'a str, &'a str)> for AccountHandleRequest<'a> {
     fn from((api_key, handle): (&'a str, &'a str)) -> Self {
         AccountHandleRequest {
             api_key,
-            handle: handle,
+            handle,
             environment: "api.strike.me",
             api_version: "v1",
         }
@@ -44,6 +49,99 @@ where
     rates_request.get::<Account>().await
 }
 
+// Ok(false) when Strike has no account with the handle
+pub async fn account_handle_exists<'a, A>(account_handle_request: A) -> Result<bool, LNError>
+where
+    A: Into<AccountHandleRequest<'a>>,
+{
+    let account_handle_request = account_handle_request.into();
+    match account_handle_request.get::<Account>().await {
+        Ok(_) => Ok(true),
+        Err(LNError::HTTPResponseError(response_error)) if response_error.status == 404 => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+// Account ids are what invoices reference as issuer_id and receiver_id
+pub struct AccountIdRequest<'a> {
+    pub api_key: &'a str,
+    pub account_id: &'a str,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> Requestable for AccountIdRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/accounts/{}/profile",
+            self.environment, self.api_version, self.account_id
+        )
+    }
+}
+
+impl<'a> From<(&'a str, &'a str)> for AccountIdRequest<'a> {
+    fn from((api_key, account_id): (&'a str, &'a str)) -> Self {
+        AccountIdRequest {
+            api_key,
+            account_id,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+pub async fn get_account_by_id<'a, A>(account_id_request: A) -> Result<Account, LNError>
+where
+    A: Into<AccountIdRequest<'a>>,
+{
+    let account_id_request = account_id_request.into();
+    account_id_request.get::<Account>().await
+}
+
+// The profile of the account the API key belongs to
+pub struct CurrentAccountRequest<'a> {
+    pub api_key: &'a str,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> Requestable for CurrentAccountRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/accounts/profile",
+            self.environment, self.api_version
+        )
+    }
+}
+
+impl<'a> From<&'a str> for CurrentAccountRequest<'a> {
+    fn from(api_key: &'a str) -> Self {
+        CurrentAccountRequest {
+            api_key,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+pub async fn get_current_account<'a, A>(current_account_request: A) -> Result<Account, LNError>
+where
+    A: Into<CurrentAccountRequest<'a>>,
+{
+    let current_account_request = current_account_request.into();
+    current_account_request.get::<Account>().await
+}
+
 
 #[cfg(test)]
 mod test {
@@ -55,7 +153,17 @@ mod test {
     async fn test_get_account_by_handel() {
         let api_key= get_api_key();
         let account_handle_request = get_account_by_handle((&api_key[..], "magog")).await;
-        println!("{:?}", account_handle_request);
         assert!(account_handle_request.is_ok());
     }
+
+    #[test]
+    fn test_account_urls() {
+        let account_id_request = AccountIdRequest::from(("api_key", "bf909224-3432-400b-895a-3010302f80f5"));
+        assert_eq!(
+            "https://api.strike.me/v1/accounts/bf909224-3432-400b-895a-3010302f80f5/profile",
+            account_id_request.get_url()
+        );
+        let current_account_request = CurrentAccountRequest::from("api_key");
+        assert_eq!("https://api.strike.me/v1/accounts/profile", current_account_request.get_url());
+    }
 }
@@ -0,0 +1,120 @@
+
+use crate::errors::{LNError};
+use crate::types::{Balance, Money};
+
+use crate::requests::request::{Requestable};
+
+pub struct BalancesRequest<'a> {
+    pub api_key: &'a str,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> Requestable for BalancesRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/balances",
+            self.environment, self.api_version
+        )
+    }
+}
+
+impl<'a> From<&'a str> for BalancesRequest<'a> {
+    fn from(api_key: &'a str) -> Self {
+        BalancesRequest {
+            api_key,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+pub async fn get_balances<'a, A>(balances_request: A) -> Result<Vec<Balance>, LNError>
+where
+    A: Into<BalancesRequest<'a>>,
+{
+    let balances_request = balances_request.into();
+    balances_request.get::<Vec<Balance>>().await
+}
+
+// Whether the available balance in the amount's currency covers it
+pub fn can_afford(balances: &[Balance], amount: &Money) -> bool {
+    balances
+        .iter()
+        .filter(|balance| balance.currency == amount.currency)
+        .any(|balance| balance.can_afford(amount))
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{CurrencyCode};
+
+    #[test]
+    fn test_balances_url() {
+        let balances_request = BalancesRequest::from("api_key");
+        assert_eq!("https://api.strike.me/v1/balances", balances_request.get_url());
+    }
+
+    #[test]
+    fn test_deserialize_balances() {
+        let balances: Vec<Balance> = serde_json::from_str(
+            r#"[
+                {
+                    "currency": "USD",
+                    "current": "120.50",
+                    "pending": "0",
+                    "outgoing": "20.00",
+                    "reserved": "0",
+                    "available": "100.50",
+                    "total": "120.50"
+                },
+                {
+                    "currency": "BTC",
+                    "current": "0.0015",
+                    "pending": "0",
+                    "outgoing": "0",
+                    "reserved": "0",
+                    "available": "0.0015",
+                    "total": "0.0015"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(balances[0].available, Money::new("100.50", CurrencyCode::Usd));
+        assert_eq!(balances[1].outgoing, Money::new("0", CurrencyCode::Btc));
+
+        let round_trip: Vec<Balance> = serde_json::from_str(&serde_json::to_string(&balances).unwrap()).unwrap();
+        assert_eq!(round_trip, balances);
+    }
+
+    #[test]
+    fn test_balance_keeps_unknown_fields() {
+        let json = r#"{"currency":"EUR","current":"5","pending":"0","outgoing":"0","reserved":"0","available":"5","total":"5","locked":"1.00"}"#;
+        let balance: Balance = serde_json::from_str(json).unwrap();
+        assert_eq!(balance.extra.get("locked"), Some(&serde_json::json!("1.00")));
+
+        let round_trip: serde_json::Value = serde_json::to_value(&balance).unwrap();
+        assert_eq!(round_trip, serde_json::from_str::<serde_json::Value>(json).unwrap());
+    }
+
+    #[test]
+    fn test_can_afford() {
+        let balances = vec![Balance {
+            currency: CurrencyCode::Usd,
+            available: Money::new("100.50", CurrencyCode::Usd),
+            ..Default::default()
+        }];
+        assert!(can_afford(&balances, &Money::new("100.5", CurrencyCode::Usd)));
+        assert!(can_afford(&balances, &Money::new("0.01", CurrencyCode::Usd)));
+        assert!(!can_afford(&balances, &Money::new("100.51", CurrencyCode::Usd)));
+        assert!(!can_afford(&balances, &Money::new("0.0001", CurrencyCode::Btc)));
+    }
+}
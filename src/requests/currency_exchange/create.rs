@@ -0,0 +1,156 @@
+
+use serde::{Serialize};
+
+use crate::errors::{LNError};
+use crate::types::{CurrencyCode, CurrencyExchangeQuote, FeePolicy, Money};
+use serde_json;
+
+use crate::requests::request::{Requestable};
+use crate::validation::{ValidationError, Validator};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyExchangeAmount {
+    pub amount: String,
+    pub currency: CurrencyCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_policy: Option<FeePolicy>,
+}
+
+// The amount is what is sold when it is in the sell currency, or what is bought when it is in the buy currency
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyExchangeQuoteData {
+    pub sell: CurrencyCode,
+    pub buy: CurrencyCode,
+    pub amount: CurrencyExchangeAmount,
+}
+
+pub struct CurrencyExchangeQuoteRequest<'a> {
+    pub api_key: &'a str,
+    pub currency_exchange_data: CurrencyExchangeQuoteData,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, CurrencyCode, CurrencyCode, Money)> for CurrencyExchangeQuoteRequest<'a> {
+    fn from((api_key, sell, buy, amount): (&'a str, CurrencyCode, CurrencyCode, Money)) -> Self {
+        CurrencyExchangeQuoteRequest {
+            api_key,
+            currency_exchange_data: CurrencyExchangeQuoteData {
+                sell,
+                buy,
+                amount: CurrencyExchangeAmount {
+                    amount: amount.amount,
+                    currency: amount.currency,
+                    fee_policy: None,
+                },
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> From<(&'a str, CurrencyCode, CurrencyCode, Money, FeePolicy)> for CurrencyExchangeQuoteRequest<'a> {
+    fn from((api_key, sell, buy, amount, fee_policy): (&'a str, CurrencyCode, CurrencyCode, Money, FeePolicy)) -> Self {
+        let mut currency_exchange_request = CurrencyExchangeQuoteRequest::from((api_key, sell, buy, amount));
+        currency_exchange_request.currency_exchange_data.amount.fee_policy = Some(fee_policy);
+        currency_exchange_request
+    }
+}
+
+impl<'a> Requestable for CurrencyExchangeQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/currency-exchange-quotes",
+            self.environment, self.api_version
+        )
+    }
+
+    fn get_body(&self) -> String {
+        serde_json::to_string(&self.currency_exchange_data).unwrap()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        let data = &self.currency_exchange_data;
+        let mut validator = Validator::new();
+        if data.sell == data.buy {
+            validator.error("buy", "must differ from the sell currency");
+        }
+        if data.amount.currency != data.sell && data.amount.currency != data.buy {
+            validator.error("amount.currency", "must be the sell or the buy currency");
+        }
+        validator.amount("amount", &data.amount.amount, data.amount.currency);
+        validator.finish()
+    }
+}
+
+pub async fn create_currency_exchange_quote<'a, A>(currency_exchange_request: A) -> Result<CurrencyExchangeQuote, LNError>
+where
+    A: Into<CurrencyExchangeQuoteRequest<'a>>,
+{
+    let currency_exchange_request = currency_exchange_request.into();
+    currency_exchange_request.post::<CurrencyExchangeQuote>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CurrencyExchangeState};
+
+    #[test]
+    fn test_serialize_currency_exchange_quote_request() {
+        let currency_exchange_request = CurrencyExchangeQuoteRequest::from((
+            "api_key",
+            CurrencyCode::Usd,
+            CurrencyCode::Btc,
+            Money::new("10.00", CurrencyCode::Usd),
+        ));
+        assert_eq!(
+            r#"{"sell":"USD","buy":"BTC","amount":{"amount":"10.00","currency":"USD"}}"#,
+            currency_exchange_request.get_body()
+        );
+        assert_eq!("https://api.strike.me/v1/currency-exchange-quotes", currency_exchange_request.get_url());
+
+        let currency_exchange_request = CurrencyExchangeQuoteRequest::from((
+            "api_key",
+            CurrencyCode::Usd,
+            CurrencyCode::Btc,
+            Money::new("0.001", CurrencyCode::Btc),
+            FeePolicy::Exclusive,
+        ));
+        assert_eq!(
+            r#"{"sell":"USD","buy":"BTC","amount":{"amount":"0.001","currency":"BTC","feePolicy":"EXCLUSIVE"}}"#,
+            currency_exchange_request.get_body()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_currency_exchange_quote() {
+        let quote: CurrencyExchangeQuote = serde_json::from_str(
+            r#"{
+                "id": "3f1e5d4c-2b1a-4c0e-9d8f-7a6b5c4d3e2f",
+                "created": "2024-05-16T09:22:37.4126893+00:00",
+                "validUntil": "2024-05-16T09:23:37.4126893+00:00",
+                "source": { "amount": "10.00", "currency": "USD" },
+                "target": { "amount": "0.00015246", "currency": "BTC" },
+                "fee": { "amount": "0.05", "currency": "USD" },
+                "conversionRate": { "amount": "65262.13", "sourceCurrency": "BTC", "targetCurrency": "USD" },
+                "state": "NEW"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(quote.state, CurrencyExchangeState::New);
+        assert!(!quote.state.is_final());
+        assert_eq!(quote.conversion_rate.source_currency, "BTC");
+        assert_eq!(quote.target, Money::new("0.00015246", CurrencyCode::Btc));
+    }
+}
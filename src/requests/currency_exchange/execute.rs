@@ -0,0 +1,62 @@
+use crate::requests::request::{Requestable};
+use crate::transport::Method;
+use crate::errors::{LNError};
+
+
+pub struct ExecuteCurrencyExchangeQuoteRequest<'a> {
+    api_key: &'a str,
+    quote_id: &'a str,
+    environment: &'a str,
+    api_version: &'a str,
+}
+
+
+impl<'a> From<(&'a str, &'a str)> for ExecuteCurrencyExchangeQuoteRequest<'a> {
+    fn from((api_key, quote_id): (&'a str, &'a str)) -> Self {
+        ExecuteCurrencyExchangeQuoteRequest {
+            api_key,
+            quote_id,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for ExecuteCurrencyExchangeQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/currency-exchange-quotes/{}/execute",
+            self.environment, self.api_version, self.quote_id
+        )
+    }
+
+    fn expected_statuses(&self, _method: Method) -> &'static [u16] {
+        &[200, 202, 204]
+    }
+}
+
+// Strike accepts the exchange without a body; use find::wait_for_currency_exchange_quote to follow it
+pub async fn execute_currency_exchange_quote<'a, A>(execute_request: A) -> Result<(), LNError>
+where
+    A: Into<ExecuteCurrencyExchangeQuoteRequest<'a>>,
+{
+    let execute_request = execute_request.into();
+    execute_request.patch_accepted().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_currency_exchange_quote_url() {
+        let execute_request = ExecuteCurrencyExchangeQuoteRequest::from(("api_key", "3f1e5d4c"));
+        assert_eq!("https://api.strike.me/v1/currency-exchange-quotes/3f1e5d4c/execute", execute_request.get_url());
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use futures_timer::Delay;
+
+use crate::cancel::CancellationToken;
+use crate::requests::request::{Requestable};
+use crate::types::{CurrencyExchangeQuote};
+use crate::errors::{LNError};
+
+
+pub struct FindCurrencyExchangeQuoteRequest<'a> {
+    api_key: &'a str,
+    quote_id: &'a str,
+    environment: &'a str,
+    api_version: &'a str,
+}
+
+
+impl<'a> From<(&'a str, &'a str)> for FindCurrencyExchangeQuoteRequest<'a> {
+    fn from((api_key, quote_id): (&'a str, &'a str)) -> Self {
+        FindCurrencyExchangeQuoteRequest {
+            api_key,
+            quote_id,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for FindCurrencyExchangeQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/currency-exchange-quotes/{}",
+            self.environment, self.api_version, self.quote_id
+        )
+    }
+}
+
+pub async fn find_currency_exchange_quote<'a, A>(find_request: A) -> Result<CurrencyExchangeQuote, LNError>
+where
+    A: Into<FindCurrencyExchangeQuoteRequest<'a>>,
+{
+    let find_request = find_request.into();
+    find_request.get::<CurrencyExchangeQuote>().await
+}
+
+// Polls an executed quote until it is Completed or Failed, or cancel is cancelled
+pub async fn wait_for_currency_exchange_quote<'a, A>(
+    find_request: A,
+    poll_interval: Duration,
+    max_attempts: u32,
+    cancel: &CancellationToken,
+) -> Result<CurrencyExchangeQuote, LNError>
+where
+    A: Into<FindCurrencyExchangeQuoteRequest<'a>>,
+{
+    let find_request = find_request.into();
+    for attempt in 0..max_attempts {
+        if attempt > 0 {
+            cancel.run(Delay::new(poll_interval)).await?;
+        }
+        let quote = cancel.run(find_request.get::<CurrencyExchangeQuote>()).await??;
+        if quote.state.is_final() {
+            return Ok(quote);
+        }
+    }
+    Err(LNError::StrikeError(format!(
+        "currency exchange quote {} did not complete after {} attempts",
+        find_request.quote_id, max_attempts
+    )))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_currency_exchange_quote_url() {
+        let find_request = FindCurrencyExchangeQuoteRequest::from(("api_key", "3f1e5d4c"));
+        assert_eq!("https://api.strike.me/v1/currency-exchange-quotes/3f1e5d4c", find_request.get_url());
+    }
+
+    #[tokio::test]
+    async fn test_wait_without_attempts() {
+        let cancel = CancellationToken::new();
+        let quote = wait_for_currency_exchange_quote(("api_key", "3f1e5d4c"), Duration::from_millis(10), 0, &cancel).await;
+        assert!(matches!(quote, Err(LNError::StrikeError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wait_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let quote = wait_for_currency_exchange_quote(("api_key", "3f1e5d4c"), Duration::from_millis(10), 3, &cancel).await;
+        assert_eq!(quote, Err(LNError::Cancelled));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::requests::request::{Requestable};
-use crate::types::{Invoice};
 use crate::errors::{LNError};
+use crate::transport::Method;
+use crate::redact::REDACTED;
+use std::fmt;
 
 
 pub struct CancelInvoiceRequest<'a> {
@@ -10,12 +12,22 @@ pub struct CancelInvoiceRequest<'a> {
     api_version: &'a str,
 }
 
+impl<'a> fmt::Debug for CancelInvoiceRequest<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancelInvoiceRequest")
+            .field("api_key", &REDACTED)
+            .field("invoice_id", &self.invoice_id)
+            .field("environment", &self.environment)
+            .field("api_version", &self.api_version)
+            .finish()
+    }
+}
 
 impl<'a> From<(&'a str, &'a str)> for CancelInvoiceRequest<'a> {
     fn from((api_key,  invoice_id): (&'a str, &'a str)) -> Self {
         CancelInvoiceRequest {
-            api_key : api_key,
-            invoice_id : invoice_id,
+            api_key,
+            invoice_id,
             environment : "api.strike.me",
             api_version : "v1",
         }
@@ -29,12 +41,15 @@ impl<'a> Requestable for CancelInvoiceRequest<'a> {
     }
 
     fn get_url(&self) -> String {
-        let url = format!(
+        format!(
             "https://{}/{}/invoices/{}/cancel",
             self.environment, self.api_version, self.invoice_id
-        );
-        println!("{}", url);
-        url
+        )
+    }
+
+    //Answered with the cancelled invoice, or accepted without a body
+    fn expected_statuses(&self, _method: Method) -> &'static [u16] {
+        &[200, 202, 204]
     }
 }
 
@@ -44,7 +59,7 @@ where
     A: Into<CancelInvoiceRequest<'a>>,
 {
     let cancel_invoice_request = cancel_invoice_request.into();
-    cancel_invoice_request.patch().await
+    cancel_invoice_request.patch_accepted().await
 }
 
 
@@ -65,23 +80,44 @@ pub mod test_invoice {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::Client;
+    use crate::errors::ResponseError;
     use crate::requests::invoices::issue::test_invoice::test_issue_invoice;
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_cancel_invoice_statuses() {
+        let mock = Arc::new(MockTransport::new());
+        let client = Client::new(mock.clone());
+        let path = "/v1/invoices/6ac1bd3e/cancel";
+
+        mock.route(Method::Patch, path, 200, r#"{"invoiceId":"6ac1bd3e","state":"CANCELLED"}"#);
+        assert_eq!(client.scope(cancel_invoice(("api_key", "6ac1bd3e"))).await, Ok(()));
+        mock.route(Method::Patch, path, 202, "");
+        assert_eq!(client.scope(cancel_invoice(("api_key", "6ac1bd3e"))).await, Ok(()));
+
+        mock.route(Method::Patch, path, 201, "{}");
+        assert_eq!(
+            client.scope(cancel_invoice(("api_key", "6ac1bd3e"))).await,
+            Err(LNError::UnexpectedStatus(ResponseError { status: 201, err: String::from("{}") }))
+        );
+        mock.route(Method::Patch, path, 422, "INVOICE_STATE_INVALID");
+        assert!(matches!(
+            client.scope(cancel_invoice(("api_key", "6ac1bd3e"))).await,
+            Err(LNError::HTTPResponseError(_))
+        ));
+    }
 
    #[tokio::test]
    async fn test_cancel_invoice() {
 
         let invoice = test_issue_invoice().await;
-
-        println!("{:?}", invoice);
-
         assert!(invoice.is_ok());
 
         let invoice_id = invoice.unwrap().invoice_id;
 
         let result = test_invoice::test_cancel_invoice(&invoice_id[..]).await;
-
-        println!("{:?}", result);
-
         assert!(result.is_ok());
     }
 }
\ No newline at end of file
@@ -2,21 +2,22 @@
 use crate::requests::request::{Requestable};
 use crate::types::{Invoice};
 use crate::errors::{LNError};
+use crate::telemetry::record_invoice_seen;
 
 
 pub struct FindInvoiceRequest<'a> {
-    api_key: &'a str,
-    invoice_id: &'a str,
-    environment: &'a str,
-    api_version: &'a str,
+    pub api_key: &'a str,
+    pub invoice_id: &'a str,
+    pub environment: &'a str,
+    pub api_version: &'a str,
 }
 
 
 impl<'a> From<(&'a str, &'a str)> for FindInvoiceRequest<'a> {
     fn from((api_key,  invoice_id): (&'a str, &'a str)) -> Self {
         FindInvoiceRequest {
-            api_key : api_key,
-            invoice_id : invoice_id,
+            api_key,
+            invoice_id,
             environment : "api.strike.me",
             api_version : "v1",
         }
@@ -42,7 +43,9 @@ where
     A: Into<FindInvoiceRequest<'a>>,
 {
     let find_invoice_request = find_invoice_request.into();
-    find_invoice_request.get::<Invoice>().await
+    let invoice = find_invoice_request.get::<Invoice>().await?;
+    record_invoice_seen(&invoice);
+    Ok(invoice)
 }
 
 
@@ -1,22 +1,24 @@
-use crate::types::{Invoices};
+use crate::cancel::CancellationToken;
+use crate::types::{Invoice, Invoices};
 use crate::errors::{LNError};
+use crate::telemetry::record_invoice_seen;
 
 use crate::requests::request::{Requestable};
 
 pub struct GetInvoicesRequest<'a> {
-    api_key: &'a str,
-    filter: Option<&'a str>,
-    order: Option<&'a str>,
-    skip: Option<u32>,
-    top: Option<u32>,
-    environment: &'a str,
-    api_version: &'a str,
+    pub api_key: &'a str,
+    pub filter: Option<&'a str>,
+    pub order: Option<&'a str>,
+    pub skip: Option<u32>,
+    pub top: Option<u32>,
+    pub environment: &'a str,
+    pub api_version: &'a str,
 }
 
 impl<'a> From<&'a str> for GetInvoicesRequest<'a> {
     fn from(api_key : &'a str) -> Self {
         GetInvoicesRequest {
-            api_key : api_key,
+            api_key,
             filter : None,
             order : None,
             skip : None,
@@ -30,11 +32,11 @@ impl<'a> From<&'a str> for GetInvoicesRequest<'a> {
 impl<'a> From<(&'a str, Option<&'a str>, Option<&'a str>, Option<u32>, Option<u32>)> for GetInvoicesRequest<'a> {
     fn from((api_key, filter, order, skip, top): (&'a str, Option<&'a str>, Option<&'a str>, Option<u32>, Option<u32>)) -> Self {
         GetInvoicesRequest {
-            api_key : api_key,
-            filter : filter,
-            order : order,
-            skip : skip,
-            top : top,
+            api_key,
+            filter,
+            order,
+            skip,
+            top,
             environment : "api.strike.me",
             api_version : "v1",
         }
@@ -55,40 +57,24 @@ impl<'a> Requestable for GetInvoicesRequest<'a> {
 
         let mut options = vec![];
 
-        match self.filter {
-            Some(filter) => {
-                options.push(format!("filter={}", filter));
-
-            },
-            None => {},
-        };
-
-        match self.order {
-            Some(order) => {
-                options.push(format!("order={}", order));
-
-            },
-            None => {},
-        };
-
-        match self.skip {
-            Some(skip) => {
-                options.push(format!("skip={}", skip));
+        if let Some(filter) = self.filter {
+            options.push(format!("$filter={}", filter));
+        }
 
-            },
-            None => {},
-        };
+        if let Some(order) = self.order {
+            options.push(format!("$orderby={}", order));
+        }
 
-        match self.top {
-            Some(top) => {
-                options.push(format!("top={}", top));
+        if let Some(skip) = self.skip {
+            options.push(format!("$skip={}", skip));
+        }
 
-            },
-            None => {},
-        };
+        if let Some(top) = self.top {
+            options.push(format!("$top={}", top));
+        }
 
-        if options.len() > 0 {
-            url.push_str("?");
+        if !options.is_empty() {
+            url.push('?');
             url.push_str(&options.join("&"));
         }
         url
@@ -100,7 +86,35 @@ where
     A: Into<GetInvoicesRequest<'a>>,
 {
     let invoice_request = invoice_request.into();
-    invoice_request.get::<Invoices>().await
+    let invoices = invoice_request.get::<Invoices>().await?;
+    invoices.items.iter().for_each(record_invoice_seen);
+    Ok(invoices)
+}
+
+// Fetches every invoice matching the request's filter, page_size at a time, starting at its skip
+pub async fn get_all_invoices<'a, A>(
+    invoice_request: A,
+    page_size: u32,
+    cancel: &CancellationToken,
+) -> Result<Vec<Invoice>, LNError>
+where
+    A: Into<GetInvoicesRequest<'a>>,
+{
+    let mut invoice_request = invoice_request.into();
+    let page_size = page_size.max(1);
+    let start = invoice_request.skip.unwrap_or(0);
+    let mut invoices = Vec::new();
+    loop {
+        invoice_request.skip = Some(start + invoices.len() as u32);
+        invoice_request.top = Some(page_size);
+        let page = cancel.run(invoice_request.get::<Invoices>()).await??;
+        page.items.iter().for_each(record_invoice_seen);
+        let fetched = page.items.len();
+        invoices.extend(page.items);
+        if fetched < page_size as usize || (start as usize + invoices.len()) as i64 >= page.count {
+            return Ok(invoices);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -126,7 +140,9 @@ pub mod test_get {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::Client;
     use crate::requests::invoices::issue;
+    use crate::transport::{HttpRequest, HttpResponse, Transport};
 
    #[tokio::test]
    async fn test_get_invoices() {
@@ -134,13 +150,65 @@ mod tests {
         assert!(invoices.is_ok());
    }
 
+    // Serves `total` invoices, honouring $skip and $top
+    struct Pages {
+        total: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for Pages {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+            let query = request.url.split_once('?').map(|(_, query)| query).unwrap_or("");
+            let option = |name: &str| {
+                query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix(name)?.parse::<usize>().ok())
+            };
+            let skip = option("$skip=").unwrap_or(0);
+            let top = option("$top=").unwrap_or(self.total);
+            let items = (skip..(skip + top).min(self.total))
+                .map(|index| Invoice {
+                    invoice_id: index.to_string(),
+                    ..Default::default()
+                })
+                .collect();
+            let page = Invoices {
+                items,
+                count: self.total as i64,
+            };
+            Ok(HttpResponse::new(200, serde_json::to_string(&page).unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_get_invoices_url() {
+        let invoice_request = GetInvoicesRequest::from(("api_key", Some("state eq 'PAID'"), Some("created desc"), Some(20), Some(10)));
+        assert_eq!(
+            invoice_request.get_url(),
+            "https://api.strike.me/v1/invoices/?$filter=state eq 'PAID'&$orderby=created desc&$skip=20&$top=10"
+        );
+        assert_eq!(GetInvoicesRequest::from("api_key").get_url(), "https://api.strike.me/v1/invoices/");
+    }
+
+    #[tokio::test]
+    async fn test_get_all_invoices() {
+        let client = Client::new(Pages { total: 5 });
+        let cancel = CancellationToken::new();
+        let invoices = client.scope(get_all_invoices("api_key", 2, &cancel)).await.unwrap();
+        let ids: Vec<&str> = invoices.iter().map(|invoice| &invoice.invoice_id[..]).collect();
+        assert_eq!(ids, vec!["0", "1", "2", "3", "4"]);
+
+        cancel.cancel();
+        let cancelled = client.scope(get_all_invoices("api_key", 2, &cancel)).await;
+        assert_eq!(cancelled, Err(LNError::Cancelled));
+    }
+
     #[tokio::test]
     async fn test_get_invoices_with_query() {
         let invoice = issue::test_invoice::test_issue_invoice().await;
         assert!(invoice.is_ok());
         let invoice_id_filter = format!("invoiceId eq {}", invoice.unwrap().invoice_id);
         let invoices  = test_get::test_get_invoices_with_query(Some(&invoice_id_filter[..]), None, None, None).await;
-        println!("{:?}", invoices);
         assert!(invoices.is_ok());
     }
 }
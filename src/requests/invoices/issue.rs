@@ -6,6 +6,11 @@ use crate::types::{Amount, Invoice};
 use serde_json;
 
 use crate::requests::request::{Requestable};
+use crate::requests::invoices::get::{get_invoices, GetInvoicesRequest};
+use crate::validation::{ValidationError, Validator, MAX_DESCRIPTION_LENGTH};
+use crate::telemetry::record_invoice_issued;
+
+pub const MAX_CORRELATION_ID_LENGTH: usize = 40;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -40,6 +45,20 @@ impl<'a> Requestable for InvoiceRequest<'a> {
     fn get_body(&self) -> String {
         serde_json::to_string(&self.invoice_request_data).unwrap()
     }
+
+    fn get_idempotency_key(&self) -> Option<&str> {
+        Some(self.invoice_request_data.correlation_id).filter(|id| !id.is_empty())
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        let data = &self.invoice_request_data;
+        Validator::new()
+            .not_empty("account_handle", self.account_handle)
+            .max_length("description", data.description, MAX_DESCRIPTION_LENGTH)
+            .max_length("correlation_id", data.correlation_id, MAX_CORRELATION_ID_LENGTH)
+            .amount_in("amount", &data.amount.amount, &data.amount.currency)
+            .finish()
+    }
 }
 
 pub async fn issue_invoice<'a, A>(invoice_request: A) -> Result<Invoice, LNError>
@@ -47,7 +66,57 @@ where
     A: Into<InvoiceRequest<'a>>,
 {
     let invoice_request = invoice_request.into();
-    invoice_request.post::<Invoice>().await
+    let invoice = invoice_request.post::<Invoice>().await?;
+    record_invoice_issued(&invoice);
+    Ok(invoice)
+}
+
+// Issues an invoice at most once per correlation id. An invoice already carrying the id is
+// returned as is, so a call retried after a timeout does not create a duplicate.
+pub async fn issue_invoice_idempotent<'a, A>(invoice_request: A) -> Result<Invoice, LNError>
+where
+    A: Into<InvoiceRequest<'a>>,
+{
+    let invoice_request = invoice_request.into();
+    let correlation_id = invoice_request.invoice_request_data.correlation_id;
+    Validator::new().not_empty("correlation_id", correlation_id).finish()?;
+    invoice_request.validate()?;
+
+    let filter = correlation_id_filter(correlation_id);
+    let existing = get_invoices(correlation_id_lookup(&invoice_request, &filter)).await?;
+    match existing
+        .items
+        .into_iter()
+        .find(|invoice| invoice.correlation_id.as_deref() == Some(correlation_id))
+    {
+        Some(invoice) => Ok(invoice),
+        None => {
+            let invoice = invoice_request.post::<Invoice>().await?;
+            record_invoice_issued(&invoice);
+            Ok(invoice)
+        }
+    }
+}
+
+// OData string literal, with the characters that would end the query string escaped
+fn correlation_id_filter(correlation_id: &str) -> String {
+    format!("correlationId eq '{}'", correlation_id.replace('\'', "''"))
+        .replace('%', "%25")
+        .replace('&', "%26")
+        .replace('#', "%23")
+        .replace('+', "%2B")
+}
+
+fn correlation_id_lookup<'a>(invoice_request: &InvoiceRequest<'a>, filter: &'a str) -> GetInvoicesRequest<'a> {
+    GetInvoicesRequest {
+        api_key: invoice_request.api_key,
+        filter: Some(filter),
+        order: None,
+        skip: None,
+        top: Some(1),
+        environment: invoice_request.environment,
+        api_version: invoice_request.api_version,
+    }
 }
 
 
@@ -80,11 +149,66 @@ pub mod test_invoice {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::correlation::{CorrelationIdGenerator, Ulid};
+    use crate::transport::Method;
+
+    #[tokio::test]
+    async fn test_correlation_id_lookup() {
+        let correlation_id = Ulid.generate();
+        let invoice_request = InvoiceRequest {
+            api_key: "api_key",
+            account_handle: "magog",
+            invoice_request_data: InvoiceRequestData {
+                description: "test_description",
+                amount: Amount {
+                    amount: "1.00".to_string(),
+                    currency: "USD".to_string(),
+                },
+                correlation_id: &correlation_id,
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        };
+        assert_eq!(invoice_request.get_idempotency_key(), Some(&correlation_id[..]));
+        assert_eq!(
+            invoice_request.build_request(Method::Post).await.unwrap().header("Idempotency-Key"),
+            Some(&correlation_id[..])
+        );
+
+        let filter = correlation_id_filter(&correlation_id);
+        assert_eq!(
+            correlation_id_lookup(&invoice_request, &filter).get_url(),
+            format!("https://api.strike.me/v1/invoices/?$filter=correlationId eq '{}'&$top=1", correlation_id)
+        );
+        assert_eq!(correlation_id_filter("it's&#1"), "correlationId eq 'it''s%26%231'");
+    }
+
+    #[tokio::test]
+    async fn test_issue_invoice_idempotent_requires_correlation_id() {
+        let invoice = issue_invoice_idempotent(InvoiceRequest {
+            api_key: "api_key",
+            account_handle: "magog",
+            invoice_request_data: InvoiceRequestData {
+                description: "test_description",
+                amount: Amount {
+                    amount: "1.00".to_string(),
+                    currency: "USD".to_string(),
+                },
+                correlation_id: "",
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        })
+        .await;
+        match invoice {
+            Err(LNError::ValidationError(err)) => assert_eq!(err.fields(), vec!["correlation_id"]),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
 
     #[tokio::test]
     async fn test_issue_invoice() {
         let invoice = test_invoice::test_issue_invoice().await;
-        println!("{:?}", invoice);
         assert!(invoice.is_ok());
     }
 }
\ No newline at end of file
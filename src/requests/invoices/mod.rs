@@ -1,5 +1,4 @@
 pub mod get;
 pub mod issue;
 pub mod find;
-// //Not working right now; Have an email into support.
-// pub mod cancel;
\ No newline at end of file
+pub mod cancel;
@@ -1,6 +1,13 @@
 pub mod quote;
-mod request;
+pub(crate) mod request;
 pub mod subscriptions;
 pub mod rates;
 pub mod accounts;
-pub mod invoices;
\ No newline at end of file
+pub mod account_cache;
+pub mod invoices;
+pub mod receive_requests;
+pub mod payment_quotes;
+pub mod payments;
+pub mod balances;
+pub mod currency_exchange;
+pub mod preflight;
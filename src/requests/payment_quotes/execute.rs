@@ -0,0 +1,64 @@
+use crate::requests::request::{Requestable};
+use crate::transport::Method;
+use crate::types::{Payment};
+use crate::errors::{LNError};
+
+
+pub struct ExecutePaymentQuoteRequest<'a> {
+    api_key: &'a str,
+    payment_quote_id: &'a str,
+    environment: &'a str,
+    api_version: &'a str,
+}
+
+
+impl<'a> From<(&'a str, &'a str)> for ExecutePaymentQuoteRequest<'a> {
+    fn from((api_key, payment_quote_id): (&'a str, &'a str)) -> Self {
+        ExecutePaymentQuoteRequest {
+            api_key,
+            payment_quote_id,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for ExecutePaymentQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/payment-quotes/{}/execute",
+            self.environment, self.api_version, self.payment_quote_id
+        )
+    }
+
+    //Completed straight away, or accepted and still pending
+    fn expected_statuses(&self, _method: Method) -> &'static [u16] {
+        &[200, 202]
+    }
+}
+
+// The returned payment is usually still Pending; use payments::find_payment to follow it
+pub async fn execute_payment_quote<'a, A>(execute_request: A) -> Result<Payment, LNError>
+where
+    A: Into<ExecutePaymentQuoteRequest<'a>>,
+{
+    let execute_request = execute_request.into();
+    execute_request.patch::<Payment>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_payment_quote_url() {
+        let execute_request = ExecutePaymentQuoteRequest::from(("api_key", "33e4f5a6"));
+        assert_eq!("https://api.strike.me/v1/payment-quotes/33e4f5a6/execute", execute_request.get_url());
+    }
+}
@@ -0,0 +1,124 @@
+
+use serde::{Serialize};
+
+use crate::errors::{LNError};
+use crate::types::{CurrencyCode, Money, PaymentQuote};
+use serde_json;
+
+use crate::requests::request::{Requestable};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LightningPaymentQuoteData<'a> {
+    pub ln_invoice: &'a str,
+    pub source_currency: CurrencyCode,
+    // Only needed when the invoice does not specify an amount
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Money>,
+}
+
+pub struct LightningPaymentQuoteRequest<'a> {
+    pub api_key: &'a str,
+    pub payment_quote_data: LightningPaymentQuoteData<'a>,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, &'a str, CurrencyCode)> for LightningPaymentQuoteRequest<'a> {
+    fn from((api_key, ln_invoice, source_currency): (&'a str, &'a str, CurrencyCode)) -> Self {
+        LightningPaymentQuoteRequest {
+            api_key,
+            payment_quote_data: LightningPaymentQuoteData {
+                ln_invoice,
+                source_currency,
+                amount: None,
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> From<(&'a str, &'a str, CurrencyCode, Money)> for LightningPaymentQuoteRequest<'a> {
+    fn from((api_key, ln_invoice, source_currency, amount): (&'a str, &'a str, CurrencyCode, Money)) -> Self {
+        LightningPaymentQuoteRequest {
+            api_key,
+            payment_quote_data: LightningPaymentQuoteData {
+                ln_invoice,
+                source_currency,
+                amount: Some(amount),
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for LightningPaymentQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/payment-quotes/lightning",
+            self.environment, self.api_version
+        )
+    }
+
+    fn get_body(&self) -> String {
+        serde_json::to_string(&self.payment_quote_data).unwrap()
+    }
+}
+
+pub async fn create_lightning_payment_quote<'a, A>(payment_quote_request: A) -> Result<PaymentQuote, LNError>
+where
+    A: Into<LightningPaymentQuoteRequest<'a>>,
+{
+    let payment_quote_request = payment_quote_request.into();
+    payment_quote_request.post::<PaymentQuote>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_lightning_payment_quote_request() {
+        let payment_quote_request = LightningPaymentQuoteRequest::from(("api_key", "lnbc1", CurrencyCode::Usd));
+        assert_eq!(r#"{"lnInvoice":"lnbc1","sourceCurrency":"USD"}"#, payment_quote_request.get_body());
+        assert_eq!("https://api.strike.me/v1/payment-quotes/lightning", payment_quote_request.get_url());
+
+        let payment_quote_request = LightningPaymentQuoteRequest::from((
+            "api_key",
+            "lnbc1",
+            CurrencyCode::Btc,
+            Money::new("0.0001", CurrencyCode::Btc),
+        ));
+        assert_eq!(
+            r#"{"lnInvoice":"lnbc1","sourceCurrency":"BTC","amount":{"amount":"0.0001","currency":"BTC"}}"#,
+            payment_quote_request.get_body()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_payment_quote() {
+        let payment_quote: PaymentQuote = serde_json::from_str(
+            r#"{
+                "paymentQuoteId": "33e4f5a6-d5b4-4e2f-8c6c-4ac1c5c0b5d1",
+                "validUntil": "2024-05-16T09:23:37.4126893+00:00",
+                "conversionRate": { "amount": "65193.45", "sourceCurrency": "BTC", "targetCurrency": "USD" },
+                "amount": { "amount": "1.00", "currency": "USD" },
+                "lightningNetworkFee": { "amount": "0.01", "currency": "USD" },
+                "totalAmount": { "amount": "1.01", "currency": "USD" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(payment_quote.description, None);
+        assert_eq!(payment_quote.lightning_network_fee, Some(Money::new("0.01", CurrencyCode::Usd)));
+        assert_eq!(payment_quote.total_amount, Money::new("1.01", CurrencyCode::Usd));
+    }
+}
@@ -0,0 +1,109 @@
+
+use serde::{Serialize};
+
+use crate::errors::{LNError};
+use crate::types::{CurrencyCode, Money, PaymentQuote};
+use serde_json;
+
+use crate::requests::request::{Requestable};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LnurlPaymentQuoteData<'a> {
+    // A Lightning Address (name@domain) or a bech32 encoded lnurl
+    pub ln_address_or_url: &'a str,
+    pub source_currency: CurrencyCode,
+    pub amount: Money,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub description: &'a str,
+}
+
+pub struct LnurlPaymentQuoteRequest<'a> {
+    pub api_key: &'a str,
+    pub payment_quote_data: LnurlPaymentQuoteData<'a>,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, &'a str, CurrencyCode, Money)> for LnurlPaymentQuoteRequest<'a> {
+    fn from((api_key, ln_address_or_url, source_currency, amount): (&'a str, &'a str, CurrencyCode, Money)) -> Self {
+        LnurlPaymentQuoteRequest {
+            api_key,
+            payment_quote_data: LnurlPaymentQuoteData {
+                ln_address_or_url,
+                source_currency,
+                amount,
+                description: "",
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> From<(&'a str, &'a str, CurrencyCode, Money, &'a str)> for LnurlPaymentQuoteRequest<'a> {
+    fn from(
+        (api_key, ln_address_or_url, source_currency, amount, description): (&'a str, &'a str, CurrencyCode, Money, &'a str),
+    ) -> Self {
+        LnurlPaymentQuoteRequest {
+            api_key,
+            payment_quote_data: LnurlPaymentQuoteData {
+                ln_address_or_url,
+                source_currency,
+                amount,
+                description,
+            },
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for LnurlPaymentQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/payment-quotes/lightning/lnurl",
+            self.environment, self.api_version
+        )
+    }
+
+    fn get_body(&self) -> String {
+        serde_json::to_string(&self.payment_quote_data).unwrap()
+    }
+}
+
+// Strike resolves the LNURL server side; see crate::lnurl to resolve it yourself
+pub async fn create_lnurl_payment_quote<'a, A>(payment_quote_request: A) -> Result<PaymentQuote, LNError>
+where
+    A: Into<LnurlPaymentQuoteRequest<'a>>,
+{
+    let payment_quote_request = payment_quote_request.into();
+    payment_quote_request.post::<PaymentQuote>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_lnurl_payment_quote_request() {
+        let payment_quote_request = LnurlPaymentQuoteRequest::from((
+            "api_key",
+            "alice@example.com",
+            CurrencyCode::Usd,
+            Money::new("5.00", CurrencyCode::Usd),
+            "Invoice 42",
+        ));
+        assert_eq!(
+            r#"{"lnAddressOrUrl":"alice@example.com","sourceCurrency":"USD","amount":{"amount":"5.00","currency":"USD"},"description":"Invoice 42"}"#,
+            payment_quote_request.get_body()
+        );
+        assert_eq!("https://api.strike.me/v1/payment-quotes/lightning/lnurl", payment_quote_request.get_url());
+    }
+}
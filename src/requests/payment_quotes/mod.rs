@@ -0,0 +1,4 @@
+pub mod lightning;
+pub mod onchain;
+pub mod lnurl;
+pub mod execute;
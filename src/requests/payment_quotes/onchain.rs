@@ -0,0 +1,246 @@
+
+use serde::{Serialize};
+
+use crate::address::Network;
+use crate::errors::{LNError};
+use crate::types::{CurrencyCode, Money, OnchainTier, PaymentQuote};
+use serde_json;
+
+use crate::requests::request::{Requestable};
+use crate::transport::Method;
+use crate::validation::{ValidationError, Validator};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainTiersData<'a> {
+    pub btc_address: &'a str,
+    pub source_currency: CurrencyCode,
+    pub amount: Money,
+}
+
+pub struct OnchainTiersRequest<'a> {
+    pub api_key: &'a str,
+    pub onchain_tiers_data: OnchainTiersData<'a>,
+    pub network: Network,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, &'a str, CurrencyCode, Money)> for OnchainTiersRequest<'a> {
+    fn from((api_key, btc_address, source_currency, amount): (&'a str, &'a str, CurrencyCode, Money)) -> Self {
+        OnchainTiersRequest {
+            api_key,
+            onchain_tiers_data: OnchainTiersData {
+                btc_address,
+                source_currency,
+                amount,
+            },
+            network: Network::Mainnet,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for OnchainTiersRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/payment-quotes/onchain/tiers",
+            self.environment, self.api_version
+        )
+    }
+
+    fn get_body(&self) -> String {
+        serde_json::to_string(&self.onchain_tiers_data).unwrap()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new()
+            .btc_address("btc_address", self.onchain_tiers_data.btc_address, self.network)
+            .finish()
+    }
+
+    //Tiers are computed rather than created
+    fn expected_statuses(&self, _method: Method) -> &'static [u16] {
+        &[200]
+    }
+}
+
+// Estimates the fee and delivery time of each on-chain tier for a payment
+pub async fn get_onchain_tiers<'a, A>(onchain_tiers_request: A) -> Result<Vec<OnchainTier>, LNError>
+where
+    A: Into<OnchainTiersRequest<'a>>,
+{
+    let onchain_tiers_request = onchain_tiers_request.into();
+    onchain_tiers_request.post::<Vec<OnchainTier>>().await
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainPaymentQuoteData<'a> {
+    pub btc_address: &'a str,
+    pub source_currency: CurrencyCode,
+    pub amount: Money,
+    pub onchain_tier_id: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub description: &'a str,
+}
+
+pub struct OnchainPaymentQuoteRequest<'a> {
+    pub api_key: &'a str,
+    pub payment_quote_data: OnchainPaymentQuoteData<'a>,
+    pub network: Network,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, &'a str, CurrencyCode, Money, &'a str)> for OnchainPaymentQuoteRequest<'a> {
+    fn from(
+        (api_key, btc_address, source_currency, amount, onchain_tier_id): (&'a str, &'a str, CurrencyCode, Money, &'a str),
+    ) -> Self {
+        OnchainPaymentQuoteRequest {
+            api_key,
+            payment_quote_data: OnchainPaymentQuoteData {
+                btc_address,
+                source_currency,
+                amount,
+                onchain_tier_id,
+                description: "",
+            },
+            network: Network::Mainnet,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+impl<'a> From<(&'a OnchainTiersRequest<'a>, &'a OnchainTier)> for OnchainPaymentQuoteRequest<'a> {
+    fn from((onchain_tiers_request, onchain_tier): (&'a OnchainTiersRequest<'a>, &'a OnchainTier)) -> Self {
+        OnchainPaymentQuoteRequest {
+            api_key: onchain_tiers_request.api_key,
+            payment_quote_data: OnchainPaymentQuoteData {
+                btc_address: onchain_tiers_request.onchain_tiers_data.btc_address,
+                source_currency: onchain_tiers_request.onchain_tiers_data.source_currency,
+                amount: onchain_tiers_request.onchain_tiers_data.amount.clone(),
+                onchain_tier_id: &onchain_tier.id[..],
+                description: "",
+            },
+            network: onchain_tiers_request.network,
+            environment: onchain_tiers_request.environment,
+            api_version: onchain_tiers_request.api_version,
+        }
+    }
+}
+
+impl<'a> Requestable for OnchainPaymentQuoteRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/payment-quotes/onchain",
+            self.environment, self.api_version
+        )
+    }
+
+    fn get_body(&self) -> String {
+        serde_json::to_string(&self.payment_quote_data).unwrap()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new()
+            .btc_address("btc_address", self.payment_quote_data.btc_address, self.network)
+            .finish()
+    }
+}
+
+// The quote is executed with payment_quotes::execute, the same as a Lightning quote
+pub async fn create_onchain_payment_quote<'a, A>(payment_quote_request: A) -> Result<PaymentQuote, LNError>
+where
+    A: Into<OnchainPaymentQuoteRequest<'a>>,
+{
+    let payment_quote_request = payment_quote_request.into();
+    payment_quote_request.post::<PaymentQuote>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+    #[test]
+    fn test_serialize_onchain_requests() {
+        let onchain_tiers_request = OnchainTiersRequest::from((
+            "api_key",
+            ADDRESS,
+            CurrencyCode::Usd,
+            Money::new("100.00", CurrencyCode::Usd),
+        ));
+        assert_eq!(
+            r#"{"btcAddress":"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4","sourceCurrency":"USD","amount":{"amount":"100.00","currency":"USD"}}"#,
+            onchain_tiers_request.get_body()
+        );
+        assert_eq!("https://api.strike.me/v1/payment-quotes/onchain/tiers", onchain_tiers_request.get_url());
+
+        let onchain_tier = OnchainTier {
+            id: "tier_fast".to_string(),
+            ..Default::default()
+        };
+        let payment_quote_request = OnchainPaymentQuoteRequest::from((&onchain_tiers_request, &onchain_tier));
+        assert_eq!(
+            r#"{"btcAddress":"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4","sourceCurrency":"USD","amount":{"amount":"100.00","currency":"USD"},"onchainTierId":"tier_fast"}"#,
+            payment_quote_request.get_body()
+        );
+        assert_eq!("https://api.strike.me/v1/payment-quotes/onchain", payment_quote_request.get_url());
+    }
+
+    #[test]
+    fn test_deserialize_onchain_tiers() {
+        let onchain_tiers: Vec<OnchainTier> = serde_json::from_str(
+            r#"[
+                {
+                    "id": "tier_fast",
+                    "estimatedDeliveryDurationInMin": 10,
+                    "estimatedFee": { "amount": "0.00002", "currency": "BTC" },
+                    "totalAmount": { "amount": "0.00152", "currency": "BTC" }
+                }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(onchain_tiers[0].estimated_delivery_duration_in_min, 10);
+        assert_eq!(onchain_tiers[0].estimated_fee, Money::new("0.00002", CurrencyCode::Btc));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_address_is_rejected_before_sending() {
+        let payment_quote = create_onchain_payment_quote((
+            "api_key",
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+            CurrencyCode::Btc,
+            Money::new("0.001", CurrencyCode::Btc),
+            "tier_fast",
+        ))
+        .await;
+        assert!(matches!(payment_quote, Err(LNError::ValidationError(error)) if error.fields() == ["btc_address"]));
+
+        //Also when the request is sent without the binding
+        let mut onchain_tiers_request = OnchainTiersRequest::from((
+            "api_key",
+            ADDRESS,
+            CurrencyCode::Usd,
+            Money::new("100.00", CurrencyCode::Usd),
+        ));
+        onchain_tiers_request.network = Network::Testnet;
+        let onchain_tiers = onchain_tiers_request.post::<Vec<OnchainTier>>().await;
+        assert!(matches!(onchain_tiers, Err(LNError::ValidationError(_))));
+    }
+}
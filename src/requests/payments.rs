@@ -0,0 +1,134 @@
+
+use std::time::Duration;
+
+use futures_timer::Delay;
+
+use crate::cancel::CancellationToken;
+use crate::errors::{LNError};
+use crate::types::{Payment};
+
+use crate::requests::request::{Requestable};
+
+pub struct FindPaymentRequest<'a> {
+    pub api_key: &'a str,
+    pub payment_id: &'a str,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> Requestable for FindPaymentRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/payments/{}",
+            self.environment, self.api_version, self.payment_id
+        )
+    }
+}
+
+impl<'a> From<(&'a str, &'a str)> for FindPaymentRequest<'a> {
+    fn from((api_key, payment_id): (&'a str, &'a str)) -> Self {
+        FindPaymentRequest {
+            api_key,
+            payment_id,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+pub async fn find_payment<'a, A>(payment_request: A) -> Result<Payment, LNError>
+where
+    A: Into<FindPaymentRequest<'a>>,
+{
+    let payment_request = payment_request.into();
+    payment_request.get::<Payment>().await
+}
+
+// Polls a payment until it is Completed or Failed, or cancel is cancelled
+pub async fn wait_for_payment<'a, A>(
+    payment_request: A,
+    poll_interval: Duration,
+    max_attempts: u32,
+    cancel: &CancellationToken,
+) -> Result<Payment, LNError>
+where
+    A: Into<FindPaymentRequest<'a>>,
+{
+    let payment_request = payment_request.into();
+    for attempt in 0..max_attempts {
+        if attempt > 0 {
+            cancel.run(Delay::new(poll_interval)).await?;
+        }
+        let payment = cancel.run(payment_request.get::<Payment>()).await??;
+        if payment.state.is_final() {
+            return Ok(payment);
+        }
+    }
+    Err(LNError::StrikeError(format!(
+        "payment {} did not complete after {} attempts",
+        payment_request.payment_id, max_attempts
+    )))
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::Client;
+    use crate::transport::{Method, MockTransport};
+    use crate::types::{PaymentState};
+
+    #[test]
+    fn test_find_payment_url() {
+        let payment_request = FindPaymentRequest::from(("api_key", "9d3e1c2b"));
+        assert_eq!("https://api.strike.me/v1/payments/9d3e1c2b", payment_request.get_url());
+    }
+
+    #[test]
+    fn test_deserialize_payment() {
+        let payment: Payment = serde_json::from_str(
+            r#"{
+                "paymentId": "9d3e1c2b-6f1a-4c6e-9f0a-0c4b2f5f1e2d",
+                "state": "FAILED",
+                "result": "FAILURE",
+                "amount": { "amount": "1.00", "currency": "USD" },
+                "totalAmount": { "amount": "1.01", "currency": "USD" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(payment.state, PaymentState::Failed);
+        assert!(payment.state.is_final());
+        assert!(!PaymentState::Pending.is_final());
+        assert_eq!(payment.completed, None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_payment() {
+        let mock = MockTransport::new();
+        mock.route(
+            Method::Get,
+            "/v1/payments/9d3e1c2b",
+            200,
+            r#"{"paymentId":"9d3e1c2b","state":"PENDING","amount":{"amount":"1.00","currency":"USD"},"totalAmount":{"amount":"1.01","currency":"USD"}}"#,
+        );
+        let client = Client::new(mock);
+        let cancel = CancellationToken::new();
+
+        let pending = client
+            .scope(wait_for_payment(("api_key", "9d3e1c2b"), Duration::from_millis(1), 2, &cancel))
+            .await;
+        assert!(matches!(pending, Err(LNError::StrikeError(_))));
+
+        cancel.cancel();
+        let cancelled = client
+            .scope(wait_for_payment(("api_key", "9d3e1c2b"), Duration::from_millis(1), 2, &cancel))
+            .await;
+        assert_eq!(cancelled, Err(LNError::Cancelled));
+    }
+}
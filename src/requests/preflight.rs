@@ -0,0 +1,126 @@
+use crate::errors::{LNError, MissingScopeError};
+use crate::requests::accounts::get_current_account;
+use crate::requests::invoices::get::get_invoices;
+use crate::requests::rates::get_rates;
+use crate::requests::subscriptions::get::get_subscriptions;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Capability {
+    Available,
+    MissingScope(MissingScopeError),
+    Unavailable(LNError),
+}
+
+impl Capability {
+    fn from_result<T>(result: Result<T, LNError>) -> Self {
+        match result {
+            Ok(_) => Capability::Available,
+            Err(LNError::MissingScope(missing_scope)) => Capability::MissingScope(missing_scope),
+            Err(err) => Capability::Unavailable(err),
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        matches!(self, Capability::Available)
+    }
+}
+
+// What the API key can do, from one cheap read per area of the API
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capabilities {
+    pub rates: Capability,
+    pub account_profile: Capability,
+    pub invoices: Capability,
+    pub subscriptions: Capability,
+}
+
+impl Capabilities {
+    pub fn all_available(&self) -> bool {
+        self.iter().all(|(_, capability)| capability.is_available())
+    }
+
+    pub fn missing_scopes(&self) -> Vec<(&'static str, &MissingScopeError)> {
+        self.iter()
+            .filter_map(|(name, capability)| match capability {
+                Capability::MissingScope(missing_scope) => Some((name, missing_scope)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &Capability)> {
+        vec![
+            ("rates", &self.rates),
+            ("account_profile", &self.account_profile),
+            ("invoices", &self.invoices),
+            ("subscriptions", &self.subscriptions),
+        ]
+        .into_iter()
+    }
+}
+
+// Meant to run at startup so a missing scope shows up before a customer request fails
+pub async fn preflight(api_key: &str) -> Capabilities {
+    Capabilities {
+        rates: Capability::from_result(get_rates(api_key).await),
+        account_profile: Capability::from_result(get_current_account(api_key).await),
+        invoices: Capability::from_result(get_invoices((api_key, None, None, None, Some(1))).await),
+        subscriptions: Capability::from_result(get_subscriptions(api_key).await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::errors::ResponseError;
+    use crate::transport::{HttpResponse, Method, MockTransport};
+
+    #[test]
+    fn test_capabilities_report() {
+        let missing_scope = MissingScopeError {
+            scope: Some(String::from("partner.webhooks.manage")),
+            err: String::from(""),
+        };
+        let capabilities = Capabilities {
+            rates: Capability::from_result(Ok(())),
+            account_profile: Capability::from_result::<()>(Err(LNError::HTTPResponseError(ResponseError {
+                status: 500,
+                err: String::from(""),
+            }))),
+            invoices: Capability::Available,
+            subscriptions: Capability::from_result::<()>(Err(LNError::MissingScope(missing_scope.clone()))),
+        };
+
+        assert!(!capabilities.all_available());
+        assert!(!capabilities.account_profile.is_available());
+        assert_eq!(capabilities.missing_scopes(), vec![("subscriptions", &missing_scope)]);
+    }
+
+    #[tokio::test]
+    async fn test_preflight() {
+        let mock = MockTransport::new();
+        mock.route(Method::Get, "/v1/rates/ticker/", 200, "[]");
+        mock.route(Method::Get, "/v1/accounts/profile", 200, r#"{"id":"a1","handle":"magog"}"#);
+        mock.route(Method::Get, "/v1/invoices/", 200, r#"{"items":[],"count":0}"#);
+        let mut forbidden = HttpResponse::new(403, "");
+        forbidden.headers.push((
+            String::from("WWW-Authenticate"),
+            String::from(r#"Bearer error="insufficient_scope", scope="partner.webhooks.manage""#),
+        ));
+        mock.respond(Method::Get, "/v1/subscriptions/", forbidden);
+
+        let capabilities = Client::new(mock).scope(preflight("api_key")).await;
+        assert!(capabilities.rates.is_available());
+        assert!(capabilities.account_profile.is_available());
+        assert!(capabilities.invoices.is_available());
+        assert_eq!(
+            capabilities.subscriptions,
+            Capability::MissingScope(MissingScopeError {
+                scope: Some(String::from("partner.webhooks.manage")),
+                err: String::from(""),
+            })
+        );
+        assert_eq!(capabilities.missing_scopes().len(), 1);
+    }
+}
@@ -1,5 +1,13 @@
+use std::time::Duration;
+
+use futures_timer::Delay;
+
+use crate::cancel::CancellationToken;
 use crate::errors::{LNError};
-use crate::types::Quote;
+use crate::requests::invoices::find::FindInvoiceRequest;
+use crate::telemetry::record_invoice_seen;
+use crate::time::Instant;
+use crate::types::{Invoice, Quote};
 use crate::requests::request::{Requestable};
 
 pub struct QuoteRequest<'a> {
@@ -30,3 +38,120 @@ where
     let quote_request = quote_request.into();
     return quote_request.post::<Quote>().await;
 }
+
+// Invoices in these states will not change again
+const FINAL_INVOICE_STATES: &[&str] = &["PAID", "CANCELLED", "FAILED"];
+
+// Keeps a live quote on offer until the invoice is paid or cancelled. Each quote is passed to
+// on_quote, the invoice is polled every poll_interval and a new quote is requested once the
+// current one expires while the invoice is in any other state, including ones this crate
+// does not know. Runs until then or until cancel is cancelled, returning the invoice in its
+// final state.
+pub async fn refresh_quote_until_paid<'a, A, F>(
+    quote_request: A,
+    poll_interval: Duration,
+    mut on_quote: F,
+    cancel: &CancellationToken,
+) -> Result<Invoice, LNError>
+where
+    A: Into<QuoteRequest<'a>>,
+    F: FnMut(&Quote),
+{
+    let quote_request = quote_request.into();
+    let find_request = FindInvoiceRequest {
+        api_key: quote_request.api_key,
+        invoice_id: quote_request.invoice_id,
+        environment: quote_request.environment,
+        api_version: quote_request.api_version,
+    };
+    let mut expires = Instant::now();
+    loop {
+        if Instant::now() >= expires {
+            let quote = cancel.run(quote_request.post::<Quote>()).await??;
+            expires = Instant::now() + Duration::from_secs(quote.expiration_in_sec.max(0) as u64);
+            on_quote(&quote);
+        }
+        cancel.run(Delay::new(poll_interval)).await?;
+        let invoice = cancel.run(find_request.get::<Invoice>()).await??;
+        record_invoice_seen(&invoice);
+        if FINAL_INVOICE_STATES.contains(&&invoice.state[..]) {
+            return Ok(invoice);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::transport::{Method, MockTransport};
+    use std::sync::Arc;
+
+    fn quote_request(invoice_id: &str) -> QuoteRequest<'_> {
+        QuoteRequest {
+            api_key: "api_key",
+            invoice_id,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+
+    // Refreshes from an invoice in state until it is paid with the second quote on offer
+    async fn refresh_until_paid_from(state: &str) {
+        let mock = Arc::new(MockTransport::new());
+        let quote = Quote {
+            quote_id: String::from("q1"),
+            expiration_in_sec: 0,
+            ..Default::default()
+        };
+        mock.route(Method::Post, "/v1/invoices/abc/quote", 201, &serde_json::to_string(&quote).unwrap());
+        let unpaid = Invoice {
+            invoice_id: String::from("abc"),
+            state: String::from(state),
+            ..Default::default()
+        };
+        mock.route(Method::Get, "/v1/invoices/abc", 200, &serde_json::to_string(&unpaid).unwrap());
+
+        let client = Client::new(mock.clone());
+        let cancel = CancellationToken::new();
+        let mut quotes = 0;
+        let refreshing = client.scope(refresh_quote_until_paid(
+            quote_request("abc"),
+            Duration::from_millis(1),
+            |quote| {
+                assert_eq!(quote.quote_id, "q1");
+                quotes += 1;
+                //Pay the invoice once the second quote is on offer
+                if quotes == 2 {
+                    let paid = Invoice {
+                        state: String::from("PAID"),
+                        ..unpaid.clone()
+                    };
+                    mock.route(Method::Get, "/v1/invoices/abc", 200, &serde_json::to_string(&paid).unwrap());
+                }
+            },
+            &cancel,
+        ));
+        assert_eq!(refreshing.await.unwrap().state, "PAID");
+        assert_eq!(quotes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_quote_until_paid() {
+        refresh_until_paid_from("UNPAID").await;
+        refresh_until_paid_from("PENDING").await;
+    }
+
+    #[tokio::test]
+    async fn test_refresh_quote_in_unknown_state() {
+        refresh_until_paid_from("AWAITING_CONFIRMATION").await;
+    }
+
+    #[tokio::test]
+    async fn test_refresh_quote_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let refreshing = refresh_quote_until_paid(quote_request("abc"), Duration::from_millis(1), |_| {}, &cancel);
+        assert_eq!(refreshing.await, Err(LNError::Cancelled));
+    }
+}
@@ -48,6 +48,9 @@ mod test {
     use tokio;
     use super::*;
     use crate::test::utils::{get_api_key};
+    use crate::client::Client;
+    use crate::transport::{Method, MockTransport};
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_get_rates() {
@@ -55,4 +58,23 @@ mod test {
         let rates = get_rates(&api_key[..]).await;
         assert!(rates.is_ok());
     }
+    #[tokio::test]
+    async fn test_get_rates_with_mock_transport() {
+        let transport = Arc::new(MockTransport::new());
+        transport.route(
+            Method::Get,
+            "/v1/rates/ticker/",
+            200,
+            r#"[{"amount":"97000.00","sourceCurrency":"BTC","targetCurrency":"USD"}]"#,
+        );
+        let client = Client::new(transport.clone());
+
+        let rates = client.scope(get_rates("api_key")).await.unwrap();
+        assert_eq!(rates[0].amount, "97000.00");
+
+        let request = transport.last_request().unwrap();
+        assert_eq!(request.url, "https://api.strike.me/v1/rates/ticker/");
+        assert_eq!(request.header("Authorization"), Some("Bearer api_key"));
+        assert_eq!(request.body, None);
+    }
 }
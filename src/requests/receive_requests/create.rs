@@ -0,0 +1,171 @@
+
+use serde::{Serialize};
+
+use crate::errors::{LNError};
+use crate::types::{CurrencyCode, Money, ReceiveRequest};
+use serde_json;
+
+use crate::requests::request::{Requestable};
+use crate::validation::{ValidationError, Validator, MAX_DESCRIPTION_LENGTH};
+
+#[derive(Default, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Bolt11Options<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Money>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_hash: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_in_seconds: Option<u64>,
+}
+
+#[derive(Default, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Money>,
+}
+
+#[derive(Default, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiveRequestData<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bolt11: Option<Bolt11Options<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onchain: Option<OnchainOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_currency: Option<CurrencyCode>,
+}
+
+pub struct CreateReceiveRequest<'a> {
+    pub api_key: &'a str,
+    pub receive_request_data: ReceiveRequestData<'a>,
+    pub environment: &'a str,
+    pub api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, ReceiveRequestData<'a>)> for CreateReceiveRequest<'a> {
+    fn from((api_key, receive_request_data): (&'a str, ReceiveRequestData<'a>)) -> Self {
+        CreateReceiveRequest {
+            api_key,
+            receive_request_data,
+            environment: "api.strike.me",
+            api_version: "v1",
+        }
+    }
+}
+
+// A reusable BOLT11 for the given amount, with no on-chain option
+impl<'a> From<(&'a str, Money, &'a str)> for CreateReceiveRequest<'a> {
+    fn from((api_key, amount, description): (&'a str, Money, &'a str)) -> Self {
+        CreateReceiveRequest::from((
+            api_key,
+            ReceiveRequestData {
+                bolt11: Some(Bolt11Options {
+                    amount: Some(amount),
+                    description: Some(description),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ))
+    }
+}
+
+impl<'a> Requestable for CreateReceiveRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/receive-requests",
+            self.environment, self.api_version
+        )
+    }
+
+    fn get_body(&self) -> String {
+        serde_json::to_string(&self.receive_request_data).unwrap()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        let data = &self.receive_request_data;
+        let mut validator = Validator::new();
+        if let Some(bolt11) = &data.bolt11 {
+            if let Some(amount) = &bolt11.amount {
+                validator.amount("bolt11.amount", &amount.amount, amount.currency);
+            }
+            if let Some(description) = bolt11.description {
+                validator.max_length("bolt11.description", description, MAX_DESCRIPTION_LENGTH);
+            }
+        }
+        if let Some(amount) = data.onchain.as_ref().and_then(|onchain| onchain.amount.as_ref()) {
+            validator.amount("onchain.amount", &amount.amount, amount.currency);
+        }
+        validator.finish()
+    }
+}
+
+pub async fn create_receive_request<'a, A>(receive_request: A) -> Result<ReceiveRequest, LNError>
+where
+    A: Into<CreateReceiveRequest<'a>>,
+{
+    let receive_request = receive_request.into();
+    receive_request.post::<ReceiveRequest>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_receive_request_data() {
+        let receive_request = CreateReceiveRequest::from((
+            "api_key",
+            ReceiveRequestData {
+                bolt11: Some(Bolt11Options {
+                    amount: Some(Money::new("1.00", CurrencyCode::Usd)),
+                    description: Some("Tip"),
+                    description_hash: None,
+                    expiry_in_seconds: Some(3600),
+                }),
+                onchain: Some(OnchainOptions::default()),
+                target_currency: Some(CurrencyCode::Btc),
+            },
+        ));
+        let expected = r#"{"bolt11":{"amount":{"amount":"1.00","currency":"USD"},"description":"Tip","expiryInSeconds":3600},"onchain":{},"targetCurrency":"BTC"}"#;
+        assert_eq!(expected, receive_request.get_body());
+        assert_eq!("https://api.strike.me/v1/receive-requests", receive_request.get_url());
+    }
+
+    #[test]
+    fn test_deserialize_receive_request() {
+        let receive_request: ReceiveRequest = serde_json::from_str(
+            r#"{
+                "receiveRequestId": "0ff9fc4f-a7be-4ab8-8a10-a7d6b0e0d1f0",
+                "created": "2024-05-16T09:22:37.4126893+00:00",
+                "targetCurrency": "BTC",
+                "bolt11": {
+                    "invoice": "lnbc10n1pnrk9m4pp5",
+                    "requestedAmount": { "amount": "1.00", "currency": "USD" },
+                    "btcAmount": "0.00001534",
+                    "description": "Tip",
+                    "paymentHash": "4a8b6c5a",
+                    "expires": "2024-05-16T10:22:37.4126893+00:00"
+                },
+                "onchain": { "address": "bc1qmxs0ps8kz4ge43g4ye4alv0l6fgqzkmuqw7ves" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(receive_request.target_currency, Some(CurrencyCode::Btc));
+        let bolt11 = receive_request.bolt11.unwrap();
+        assert_eq!(bolt11.requested_amount, Some(Money::new("1.00", CurrencyCode::Usd)));
+        assert_eq!(bolt11.description_hash, None);
+        assert_eq!(receive_request.onchain.unwrap().amount, None);
+    }
+}
@@ -0,0 +1,58 @@
+
+use crate::requests::request::{Requestable};
+use crate::types::{ReceiveRequest};
+use crate::errors::{LNError};
+
+
+pub struct FindReceiveRequest<'a> {
+    api_key: &'a str,
+    receive_request_id: &'a str,
+    environment: &'a str,
+    api_version: &'a str,
+}
+
+
+impl<'a> From<(&'a str, &'a str)> for FindReceiveRequest<'a> {
+    fn from((api_key, receive_request_id): (&'a str, &'a str)) -> Self {
+        FindReceiveRequest {
+            api_key,
+            receive_request_id,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for FindReceiveRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/receive-requests/{}",
+            self.environment, self.api_version, self.receive_request_id
+        )
+    }
+}
+
+pub async fn find_receive_request<'a, A>(find_receive_request: A) -> Result<ReceiveRequest, LNError>
+where
+    A: Into<FindReceiveRequest<'a>>,
+{
+    let find_receive_request = find_receive_request.into();
+    find_receive_request.get::<ReceiveRequest>().await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_receive_request_url() {
+        let find_receive_request = FindReceiveRequest::from(("api_key", "0ff9fc4f"));
+        assert_eq!("https://api.strike.me/v1/receive-requests/0ff9fc4f", find_receive_request.get_url());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::types::{Receives};
+use crate::errors::{LNError};
+
+use crate::requests::request::{Requestable};
+
+// Lists receives across all receive requests, optionally filtered with OData
+pub struct GetReceivesRequest<'a> {
+    api_key: &'a str,
+    filter: Option<&'a str>,
+    order: Option<&'a str>,
+    skip: Option<u32>,
+    top: Option<u32>,
+    environment: &'a str,
+    api_version: &'a str,
+}
+
+impl<'a> From<&'a str> for GetReceivesRequest<'a> {
+    fn from(api_key : &'a str) -> Self {
+        GetReceivesRequest {
+            api_key,
+            filter : None,
+            order : None,
+            skip : None,
+            top : None,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> From<(&'a str, Option<&'a str>, Option<&'a str>, Option<u32>, Option<u32>)> for GetReceivesRequest<'a> {
+    fn from((api_key, filter, order, skip, top): (&'a str, Option<&'a str>, Option<&'a str>, Option<u32>, Option<u32>)) -> Self {
+        GetReceivesRequest {
+            api_key,
+            filter,
+            order,
+            skip,
+            top,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for GetReceivesRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        let mut url = format!(
+            "https://{}/{}/receive-requests/receives",
+            self.environment, self.api_version
+        );
+
+        let mut options = vec![];
+
+        if let Some(filter) = self.filter {
+            options.push(format!("$filter={}", filter));
+        }
+
+        if let Some(order) = self.order {
+            options.push(format!("$orderby={}", order));
+        }
+
+        if let Some(skip) = self.skip {
+            options.push(format!("$skip={}", skip));
+        }
+
+        if let Some(top) = self.top {
+            options.push(format!("$top={}", top));
+        }
+
+        if !options.is_empty() {
+            url.push('?');
+            url.push_str(&options.join("&"));
+        }
+        url
+    }
+}
+
+pub async fn get_receives<'a, A>(receives_request: A) -> Result<Receives, LNError>
+where
+    A: Into<GetReceivesRequest<'a>>,
+{
+    let receives_request = receives_request.into();
+    receives_request.get::<Receives>().await
+}
+
+// Lists the receive events (payments) made against a single receive request
+pub struct ReceiveRequestReceivesRequest<'a> {
+    api_key: &'a str,
+    receive_request_id: &'a str,
+    environment: &'a str,
+    api_version: &'a str,
+}
+
+impl<'a> From<(&'a str, &'a str)> for ReceiveRequestReceivesRequest<'a> {
+    fn from((api_key, receive_request_id): (&'a str, &'a str)) -> Self {
+        ReceiveRequestReceivesRequest {
+            api_key,
+            receive_request_id,
+            environment : "api.strike.me",
+            api_version : "v1",
+        }
+    }
+}
+
+impl<'a> Requestable for ReceiveRequestReceivesRequest<'a> {
+
+    fn get_api_key(&self) -> &str {
+        self.api_key
+    }
+
+    fn get_url(&self) -> String {
+        format!(
+            "https://{}/{}/receive-requests/{}/receives",
+            self.environment, self.api_version, self.receive_request_id
+        )
+    }
+}
+
+pub async fn get_receive_request_receives<'a, A>(receives_request: A) -> Result<Receives, LNError>
+where
+    A: Into<ReceiveRequestReceivesRequest<'a>>,
+{
+    let receives_request = receives_request.into();
+    receives_request.get::<Receives>().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CurrencyCode, Money, ReceiveState, ReceiveType};
+
+    #[test]
+    fn test_get_receives_url() {
+        let receives_request = GetReceivesRequest::from("api_key");
+        assert_eq!("https://api.strike.me/v1/receive-requests/receives", receives_request.get_url());
+
+        let receives_request = GetReceivesRequest::from(("api_key", Some("state eq 'COMPLETED'"), Some("created desc"), None, Some(10)));
+        assert_eq!(
+            "https://api.strike.me/v1/receive-requests/receives?$filter=state eq 'COMPLETED'&$orderby=created desc&$top=10",
+            receives_request.get_url()
+        );
+
+        let receives_request = ReceiveRequestReceivesRequest::from(("api_key", "0ff9fc4f"));
+        assert_eq!("https://api.strike.me/v1/receive-requests/0ff9fc4f/receives", receives_request.get_url());
+    }
+
+    #[test]
+    fn test_deserialize_receives() {
+        let receives: Receives = serde_json::from_str(
+            r#"{
+                "items": [
+                    {
+                        "receiveId": "bdbb3b64-4a2e-4d2b-9a0b-2b7c7d1e1c10",
+                        "receiveRequestId": "0ff9fc4f-a7be-4ab8-8a10-a7d6b0e0d1f0",
+                        "type": "ONCHAIN",
+                        "state": "COMPLETED",
+                        "amountReceived": { "amount": "0.0001", "currency": "BTC" },
+                        "amountCredited": { "amount": "0.0001", "currency": "BTC" },
+                        "created": "2024-05-16T09:30:00+00:00",
+                        "completed": "2024-05-16T09:45:00+00:00",
+                        "onchain": {
+                            "address": "bc1qmxs0ps8kz4ge43g4ye4alv0l6fgqzkmuqw7ves",
+                            "txId": "8c4f5f8b"
+                        }
+                    }
+                ],
+                "count": 1
+            }"#,
+        )
+        .unwrap();
+
+        let receive = &receives.items[0];
+        assert_eq!(receive.receive_type, ReceiveType::Onchain);
+        assert_eq!(receive.state, ReceiveState::Completed);
+        assert_eq!(receive.amount_received, Money::new("0.0001", CurrencyCode::Btc));
+        assert_eq!(receive.lightning, None);
+        assert_eq!(receive.onchain.as_ref().unwrap().tx_id, Some("8c4f5f8b".to_string()));
+    }
+}
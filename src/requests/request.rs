@@ -1,143 +1,226 @@
-use reqwest;
-use reqwest::header::{HeaderMap, HeaderValue};
-use crate::errors::{LNError, ResponseError};
+use crate::client::{send_or_render, Client};
+use crate::dry_run::placeholder_json;
+use crate::endpoint::BodyKind;
+use crate::errors::{JsonError, LNError, MissingScopeError, ResponseError};
+use crate::transport::{HttpRequest, HttpResponse, Method};
+use crate::validation::ValidationError;
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 
-#[async_trait]
+// Interpreting a response is kept apart from sending the request so that any transport,
+// or a test, can feed a response through the same checks.
+
+// The statuses Strike answers a successful request with, unless the endpoint says otherwise
+pub(crate) fn expected_statuses(method: Method) -> &'static [u16] {
+    match method {
+        Method::Post => &[201],
+        Method::Get => &[200],
+        Method::Delete => &[204],
+        Method::Patch => &[200],
+    }
+}
+
+pub(crate) fn expected_body(method: Method) -> BodyKind {
+    match method {
+        Method::Delete => BodyKind::Empty,
+        _ => BodyKind::Json,
+    }
+}
+
+// The body of an expected status as T, anything else as an error. A missing or ignored
+// body is read as null, so T is () or an Option. Success statuses the endpoint does not
+// expect are UnexpectedStatus rather than an error from Strike.
+pub(crate) fn interpret<T: DeserializeOwned>(response: HttpResponse, expected: &[u16], body: BodyKind) -> Result<T, LNError> {
+    if !expected.contains(&response.status) {
+        return Err(match response.is_success() {
+            true => LNError::UnexpectedStatus(ResponseError { status: response.status, err: response.body }),
+            false => error_from_response(response),
+        });
+    }
+    let json = match body {
+        BodyKind::Empty => "null",
+        BodyKind::Either if response.body.trim().is_empty() => "null",
+        _ => &response.body,
+    };
+    serde_json::from_str::<T>(json).map_err(|err| LNError::from(JsonError::new(err, &response.body)))
+}
+
+fn error_from_response(response: HttpResponse) -> LNError {
+    let www_authenticate = response.header("www-authenticate").map(String::from);
+    classify_error(response.status, www_authenticate.as_deref(), response.body)
+}
+
+// A 403 is a MissingScope when the challenge header or the body blames the scopes
+fn classify_error(status: u16, www_authenticate: Option<&str>, body: String) -> LNError {
+    if status == 403 {
+        let challenge = www_authenticate.unwrap_or("");
+        if challenge.contains("insufficient_scope") || body.to_ascii_lowercase().contains("scope") {
+            let scope = challenge
+                .split("scope=\"")
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+                .map(String::from);
+            return LNError::MissingScope(MissingScopeError { scope, err: body });
+        }
+    }
+    LNError::HTTPResponseError(ResponseError { status, err: body })
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait Requestable {
 
     fn get_api_key(&self) -> &str;
 
-    fn get_authorization_headers(&self) -> HeaderMap 
+    fn get_authorization_headers(&self) -> Vec<(String, String)>
     {
-        let mut headers = HeaderMap::new();
-        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
-        headers.insert("Accept", HeaderValue::from_static("application/json"));
-        headers.insert(
-            "Authorization",
-            HeaderValue::from_str(&format!("Bearer {}", self.get_api_key())).unwrap(),
-        );
-        return headers;
+        let mut headers = vec![
+            (String::from("Content-Type"), String::from("application/json")),
+            (String::from("Accept"), String::from("application/json")),
+            (String::from("Authorization"), format!("Bearer {}", self.get_api_key())),
+        ];
+        if let Some(key) = self.get_idempotency_key() {
+            headers.push((String::from("Idempotency-Key"), key.to_string()));
+        }
+        headers
     }
 
     fn get_url(&self) -> String;
 
     fn get_body(&self) -> String {
-        return String::from("{}");
+        String::from("{}")
     }
 
-    async fn post<T : DeserializeOwned>(&self) -> Result<T, LNError> 
-    {
-        let url = self.get_url();
-        let body = self.get_body();
-        let response = reqwest::Client::builder()
-            .default_headers(self.get_authorization_headers())
-            .build()?
-            .post(&url)
-            .body(body)
-            .send()
-            .await
-            .map_err(|e| LNError::HTTPError(e.to_string()))?;
-
-        //There must be a better way to do this
-        match response.status() {
-            reqwest::StatusCode::CREATED => {
-                response.json::<T>().await.map_err(|err| {
-                    LNError::JsonError(err.to_string())
-                })
-            },
-            _ => {
-                Err(LNError::HTTPResponseError(ResponseError {
-                    status: response.status().as_u16(),
-                    err: response.text().await.unwrap_or("".to_string()),
-                    })
-                )
-            }
+    //Sent as Idempotency-Key so a retried create is not applied twice
+    fn get_idempotency_key(&self) -> Option<&str> {
+        None
+    }
+
+    //Run before anything is sent
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    fn expected_statuses(&self, method: Method) -> &'static [u16] {
+        expected_statuses(method)
+    }
+
+    fn expected_body(&self, method: Method) -> BodyKind {
+        expected_body(method)
+    }
+
+    //Authorized by the current client's auth provider when it has one, otherwise by the api
+    //key, sent to the client's base URL and carrying its default headers
+    async fn build_request(&self, method: Method) -> Result<HttpRequest, LNError> {
+        let client = Client::current();
+        let mut request = HttpRequest::new(method, client.rebase(self.get_url()));
+        request.headers = self.get_authorization_headers();
+        if let Some(token) = client.access_token().await? {
+            request.set_header("Authorization", format!("Bearer {}", token));
         }
+        client.apply_headers(&mut request);
+        if matches!(method, Method::Post | Method::Patch) {
+            request.body = Some(self.get_body());
+        }
+        Ok(request)
     }
 
-    async fn get<T : DeserializeOwned>(&self) -> Result<T, LNError> 
+    //In dry run the response is read from a placeholder with the first expected status
+    async fn send_as<T : DeserializeOwned>(&self, method: Method) -> Result<T, LNError>
     {
-        let url = self.get_url();
-        let response = reqwest::Client::builder()
-            .default_headers(self.get_authorization_headers())
-            .build()?
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| LNError::HTTPError(e.to_string()))?;
-
-        //There must be a better way to do this
-        match response.status() {
-            reqwest::StatusCode::OK => {
-                response.json::<T>().await.map_err(|err| {
-                    LNError::JsonError(err.to_string())
-                })
-            },
-            _ => {
-                Err(LNError::HTTPResponseError(ResponseError {
-                    status: response.status().as_u16(),
-                    err: response.text().await.unwrap_or("".to_string()),
-                    })
-                )
-            }
-        }
+        self.validate()?;
+        let (statuses, body) = (self.expected_statuses(method), self.expected_body(method));
+        let response = send_or_render(self.build_request(method).await?, || placeholder_json::<T>(statuses, body)).await?;
+        interpret(response, statuses, body)
     }
 
-    async fn delete(&self) -> Result<(), LNError> 
+    async fn post<T : DeserializeOwned>(&self) -> Result<T, LNError>
     {
-        let url = self.get_url();
-        let response = reqwest::Client::builder()
-            .default_headers(self.get_authorization_headers())
-            .build()?
-            .delete(&url)
-            .send()
-            .await
-            .map_err(|e| LNError::HTTPError(e.to_string()))?;
-
-        //There must be a better way to do this
-        match response.status() {
-            reqwest::StatusCode::NO_CONTENT => {
-                Ok(())
-            },
-            _ => {
-                Err(LNError::HTTPResponseError(ResponseError {
-                    status: response.status().as_u16(),
-                    err: response.text().await.unwrap_or("".to_string()),
-                    })
-                )
-            }
-        }
+        self.send_as(Method::Post).await
     }
 
-    async fn patch<T : DeserializeOwned>(&self) -> Result<T, LNError> 
+    async fn get<T : DeserializeOwned>(&self) -> Result<T, LNError>
     {
-        let url = self.get_url();
-        let body = self.get_body();
-        let response = reqwest::Client::builder()
-            .default_headers(self.get_authorization_headers())
-            .build()?
-            .patch(&url)
-            .body(body)
-            .send()
-            .await
-            .map_err(|e| LNError::HTTPError(e.to_string()))?;
-
-        //There must be a better way to do this
-        match response.status() {
-            reqwest::StatusCode::OK => {
-                response.json::<T>().await.map_err(|err| {
-                    LNError::JsonError(err.to_string())
-                })
-            },
-            _ => {
-                Err(LNError::HTTPResponseError(ResponseError {
-                    status: response.status().as_u16(),
-                    err: response.text().await.unwrap_or("".to_string()),
-                    })
-                )
-            }
-        }
+        self.send_as(Method::Get).await
+    }
+
+    async fn delete(&self) -> Result<(), LNError>
+    {
+        self.send_as(Method::Delete).await
+    }
+
+    async fn patch<T : DeserializeOwned>(&self) -> Result<T, LNError>
+    {
+        self.send_as(Method::Patch).await
+    }
+
+    //For actions that are accepted without returning the resource
+    async fn patch_accepted(&self) -> Result<(), LNError>
+    {
+        self.validate()?;
+        let statuses = self.expected_statuses(Method::Patch);
+        let response = send_or_render(self.build_request(Method::Patch).await?, || placeholder_json::<()>(statuses, BodyKind::Empty)).await?;
+        interpret(response, statuses, BodyKind::Empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_error() {
+        let error = classify_error(
+            403,
+            Some(r#"Bearer error="insufficient_scope", scope="partner.invoice.read""#),
+            String::from(""),
+        );
+        assert_eq!(
+            error,
+            LNError::MissingScope(MissingScopeError {
+                scope: Some(String::from("partner.invoice.read")),
+                err: String::from(""),
+            })
+        );
+
+        let body = String::from(r#"{"data":{"status":403,"code":"FORBIDDEN","message":"Missing required scope"}}"#);
+        let error = classify_error(403, None, body.clone());
+        assert_eq!(error, LNError::MissingScope(MissingScopeError { scope: None, err: body }));
+
+        let error = classify_error(403, None, String::from("Forbidden"));
+        assert_eq!(error, LNError::HTTPResponseError(ResponseError { status: 403, err: String::from("Forbidden") }));
+
+        let error = classify_error(404, None, String::from("scope"));
+        assert!(matches!(error, LNError::HTTPResponseError(_)));
+    }
+
+    #[test]
+    fn test_interpret() {
+        let ok: Result<Vec<u32>, LNError> = interpret(HttpResponse::new(200, "[1,2]"), &[200], BodyKind::Json);
+        assert_eq!(ok, Ok(vec![1, 2]));
+
+        let unexpected: Result<Vec<u32>, LNError> = interpret(HttpResponse::new(201, "[1,2]"), &[200], BodyKind::Json);
+        assert_eq!(
+            unexpected,
+            Err(LNError::UnexpectedStatus(ResponseError { status: 201, err: String::from("[1,2]") }))
+        );
+
+        let malformed: Result<Vec<u32>, LNError> = interpret(HttpResponse::new(200, "{}"), &[200], BodyKind::Json);
+        assert!(matches!(malformed, Err(LNError::JsonError(_))));
+
+        let missing: Result<Vec<u32>, LNError> = interpret(HttpResponse::new(200, ""), &[200], BodyKind::Json);
+        assert!(matches!(missing, Err(LNError::JsonError(_))));
+        let either: Result<Option<Vec<u32>>, LNError> = interpret(HttpResponse::new(202, ""), &[202], BodyKind::Either);
+        assert_eq!(either, Ok(None));
+        let either: Result<Option<Vec<u32>>, LNError> = interpret(HttpResponse::new(200, "[3]"), &[200], BodyKind::Either);
+        assert_eq!(either, Ok(Some(vec![3])));
+
+        let mut forbidden = HttpResponse::new(403, "");
+        forbidden.headers.push((
+            String::from("WWW-Authenticate"),
+            String::from(r#"Bearer error="insufficient_scope", scope="partner.rates.ticker""#),
+        ));
+        assert!(matches!(interpret::<()>(forbidden, &[204], BodyKind::Empty), Err(LNError::MissingScope(_))));
+        assert_eq!(interpret(HttpResponse::new(200, "{\"ignored\":true}"), &[200, 204], BodyKind::Empty), Ok(()));
     }
-}
\ No newline at end of file
+}
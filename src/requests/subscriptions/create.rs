@@ -2,12 +2,15 @@ use crate::types::{Event, Subscription};
 use crate::errors::{LNError};
 use serde::{Serialize};
 use serde_json;
+use std::fmt;
+use crate::redact::REDACTED;
 use rand::{thread_rng, Rng};
 use rand::distributions::Alphanumeric;
 
 use crate::requests::request::{Requestable};
+use crate::validation::{ValidationError, Validator};
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize)]
+#[derive(Default, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSubscriptionRequest<'a> {
     #[serde(skip_serializing)]
@@ -23,6 +26,21 @@ pub struct CreateSubscriptionRequest<'a> {
     api_version: &'a str,
 }
 
+impl<'a> fmt::Debug for CreateSubscriptionRequest<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CreateSubscriptionRequest")
+            .field("api_key", &REDACTED)
+            .field("webhook_url", &self.webhook_url)
+            .field("webhook_version", &self.webhook_version)
+            .field("secret", &REDACTED)
+            .field("enabled", &self.enabled)
+            .field("event_types", &self.event_types)
+            .field("environment", &self.environment)
+            .field("api_version", &self.api_version)
+            .finish()
+    }
+}
+
 impl<'a> From<(&'a str, &'a str, Vec<Event>)> for CreateSubscriptionRequest<'a> {
     fn from((api_key, web_hookurl, event_types) : (&'a str, &'a str, Vec<Event>)) -> Self {
 
@@ -38,7 +56,7 @@ impl<'a> From<(&'a str, &'a str, Vec<Event>)> for CreateSubscriptionRequest<'a>
             api_key,
             webhook_url: web_hookurl,
             webhook_version: "v1",
-            secret: secret,
+            secret,
             enabled: true,
             event_types,
             environment: "api.strike.me",
@@ -64,6 +82,14 @@ impl<'a> Requestable for CreateSubscriptionRequest<'a> {
         serde_json::to_string(&self).unwrap()
     }
 
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut validator = Validator::new();
+        validator.https_url("webhook_url", self.webhook_url);
+        if self.event_types.is_empty() {
+            validator.error("event_types", "must not be empty");
+        }
+        validator.finish()
+    }
 
 }
 
@@ -84,6 +110,7 @@ where
                 created: subscription.created,
                 secret: subscription_request.secret,
                 event_types: subscription_request.event_types,
+                extra: subscription.extra,
             }})
         }
         Err(err) => Err(err),
@@ -110,6 +137,7 @@ pub mod tests {
     use super::*;
 
     use crate::requests::subscriptions::create::test_create;
+    use crate::transport::Method;
     use crate::requests::subscriptions::delete::test_delete::test_delete_subscription;
 
     #[test]
@@ -129,10 +157,32 @@ pub mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[tokio::test]
+    async fn test_debug_redacts_secrets() {
+        let subscription_request = CreateSubscriptionRequest::from(("api_key", "https://example.com/hook", vec![Event::InvoiceCreated]));
+        let debug = format!("{:?}", subscription_request);
+        assert!(!debug.contains("api_key\""));
+        assert!(!debug.contains(&subscription_request.secret));
+        assert!(debug.contains("https://example.com/hook"));
+
+        let request = subscription_request.build_request(Method::Post).await.unwrap();
+        let debug = format!("{:?}", request);
+        assert!(!debug.contains("Bearer api_key"));
+        assert!(!debug.contains(&subscription_request.secret));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_requires_https_webhook() {
+        let subscription = subscribe(("api_key", "http://example.com/hook", vec![])).await;
+        match subscription {
+            Err(LNError::ValidationError(err)) => assert_eq!(err.fields(), vec!["webhook_url", "event_types"]),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
     #[tokio::test]
     pub async fn test_create_subscription() {
         let subscription = test_create::test_create_subscription().await;
-        println!("{:?}", subscription);
         assert!(subscription.is_ok());
         test_delete_subscription( &subscription.unwrap().id[..]).await;
     }
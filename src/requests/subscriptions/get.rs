@@ -12,7 +12,7 @@ pub struct GetSubscriptionsRequest<'a> {
 impl<'a> From<&'a str> for GetSubscriptionsRequest<'a> {
     fn from(api_key : &'a str) -> Self {
         GetSubscriptionsRequest {
-            api_key : api_key,
+            api_key,
             environment : "api.strike.me",
             api_version : "v1",
         }
@@ -3,4 +3,4 @@ pub mod update;
 pub mod get;
 pub mod delete;
 pub mod find;
-mod subscription;
\ No newline at end of file
+pub mod subscription;
\ No newline at end of file
@@ -10,8 +10,8 @@ pub struct SubscriptionsRequest<'a> {
 impl<'a> From<(&'a str, &'a str)> for SubscriptionsRequest<'a> {
     fn from((api_key, subscription_id) : (&'a str, &'a str)) -> Self {
         SubscriptionsRequest {
-            api_key : api_key,
-            subscription_id: subscription_id,
+            api_key,
+            subscription_id,
             environment : "api.strike.me",
             api_version : "v1",
         }
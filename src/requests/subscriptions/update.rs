@@ -1,9 +1,13 @@
 use crate::types::{Subscription, Event};
 use crate::requests::request::{Requestable};
+use crate::transport::Method;
+use crate::validation::{ValidationError, Validator};
 use crate::errors::{LNError};
 use serde::{Serialize};
+use std::fmt;
+use crate::redact::REDACTED;
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize)]
+#[derive(Default, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateSubscriptionRequest<'a> {
     api_key: &'a str,
@@ -20,6 +24,22 @@ pub struct UpdateSubscriptionRequest<'a> {
     api_version: &'a str,
 }
 
+impl<'a> fmt::Debug for UpdateSubscriptionRequest<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UpdateSubscriptionRequest")
+            .field("api_key", &REDACTED)
+            .field("subscription_id", &self.subscription_id)
+            .field("webhook_url", &self.webhook_url)
+            .field("webhook_version", &self.webhook_version)
+            .field("secret", &REDACTED)
+            .field("enabled", &self.enabled)
+            .field("event_types", &self.event_types)
+            .field("environment", &self.environment)
+            .field("api_version", &self.api_version)
+            .finish()
+    }
+}
+
 impl<'a> Requestable for UpdateSubscriptionRequest<'a> {
 
     fn get_api_key(&self) -> &str {
@@ -36,6 +56,22 @@ impl<'a> Requestable for UpdateSubscriptionRequest<'a> {
     fn get_body(&self) -> String {
         serde_json::to_string(&self).unwrap()
     }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut validator = Validator::new();
+        validator
+            .not_empty("subscription_id", self.subscription_id)
+            .https_url("webhook_url", self.webhook_url);
+        if self.event_types.is_empty() {
+            validator.error("event_types", "must not be empty");
+        }
+        validator.finish()
+    }
+
+    //The updated subscription is returned
+    fn expected_statuses(&self, _method: Method) -> &'static [u16] {
+        &[200]
+    }
 }
 
 impl<'a> From<(&'a str, &'a Subscription)> for UpdateSubscriptionRequest<'a> {
@@ -85,7 +121,6 @@ mod tests {
 
         //Update the subscription
         let subscription_request = update_subscription((&api_key[..], &subscription)).await;
-        println!("{:?}", subscription_request);
         assert!(subscription_request.is_ok());
 
         let updated_subscription = subscription_request.unwrap();
@@ -0,0 +1,206 @@
+use crate::errors::LNError;
+use crate::redact::{redact_body, redact_headers};
+use crate::telemetry::find_trace_id;
+use crate::transport::HttpResponse;
+use std::cell::RefCell;
+use std::fmt::{self, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+// The HTTP response behind any call, for support escalations and rate limit handling.
+// Wrapping a call's future returns the metadata of the last response it received next to
+// its result, whether or not the call succeeded. There is none when nothing was received,
+// e.g. on a connection error or when the call was answered from a cache.
+//
+//     let (invoice, meta) = with_response_meta(find_invoice((api_key, invoice_id))).await;
+//     if let (Err(LNError::HTTPResponseError(_)), Some(meta)) = (&invoice, &meta) {
+//         log::warn!("request {:?} failed, retry in {:?}s", meta.request_id(), meta.rate_limit_reset());
+//     }
+//
+// As with Client::scope, requests made by tasks spawned inside the future are not seen.
+
+#[derive(Clone, Default, PartialEq)]
+pub struct ResponseMeta {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl ResponseMeta {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // The id to quote to Strike support
+    pub fn request_id(&self) -> Option<&str> {
+        find_trace_id(&self.headers)
+    }
+
+    pub fn rate_limit_limit(&self) -> Option<u64> {
+        self.number(&["x-ratelimit-limit", "ratelimit-limit"])
+    }
+
+    pub fn rate_limit_remaining(&self) -> Option<u64> {
+        self.number(&["x-ratelimit-remaining", "ratelimit-remaining"])
+    }
+
+    // Seconds until the limit resets
+    pub fn rate_limit_reset(&self) -> Option<u64> {
+        self.number(&["x-ratelimit-reset", "ratelimit-reset"])
+    }
+
+    fn number(&self, names: &[&str]) -> Option<u64> {
+        names
+            .iter()
+            .find_map(|name| self.header(name))
+            .and_then(|value| value.trim().parse().ok())
+    }
+}
+
+impl From<&HttpResponse> for ResponseMeta {
+    fn from(response: &HttpResponse) -> Self {
+        ResponseMeta {
+            status: response.status,
+            headers: response.headers.clone(),
+            body: response.body.clone(),
+        }
+    }
+}
+
+// Credentials in headers and body are redacted
+impl fmt::Debug for ResponseMeta {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ResponseMeta")
+            .field("status", &self.status)
+            .field("headers", &redact_headers(&self.headers))
+            .field("body", &redact_body(&self.body))
+            .finish()
+    }
+}
+
+type Slot = Arc<Mutex<Option<ResponseMeta>>>;
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Slot>> = const { RefCell::new(None) };
+}
+
+// Called by the client with every response it returns
+pub(crate) fn record(response: &HttpResponse) {
+    CAPTURE.with(|capture| {
+        if let Some(slot) = capture.borrow().as_ref() {
+            *slot.lock().unwrap() = Some(ResponseMeta::from(response));
+        }
+    });
+}
+
+pub fn with_response_meta<T, F>(future: F) -> WithResponseMeta<F>
+where
+    F: Future<Output = Result<T, LNError>>,
+{
+    WithResponseMeta {
+        slot: Slot::default(),
+        future: Box::pin(future),
+    }
+}
+
+pub struct WithResponseMeta<F> {
+    slot: Slot,
+    future: Pin<Box<F>>,
+}
+
+impl<T, F> Future for WithResponseMeta<F>
+where
+    F: Future<Output = Result<T, LNError>>,
+{
+    type Output = (Result<T, LNError>, Option<ResponseMeta>);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let previous = CAPTURE.with(|capture| capture.replace(Some(self.slot.clone())));
+        struct Restore(Option<Slot>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CAPTURE.with(|capture| *capture.borrow_mut() = self.0.take());
+            }
+        }
+        let restore = Restore(previous);
+        let result = self.future.as_mut().poll(cx);
+        drop(restore);
+
+        result.map(|result| (result, self.slot.lock().unwrap().take()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::errors::JsonError;
+    use crate::requests::balances::get_balances;
+    use crate::requests::rates::get_rates;
+    use crate::transport::{Method, MockTransport};
+    use std::sync::Arc;
+
+    fn response(body: &str) -> HttpResponse {
+        let mut response = HttpResponse::new(200, body);
+        response.headers = vec![
+            (String::from("X-Request-Id"), String::from("req-42")),
+            (String::from("X-RateLimit-Remaining"), String::from("99")),
+            (String::from("X-RateLimit-Reset"), String::from("30")),
+        ];
+        response
+    }
+
+    #[tokio::test]
+    async fn test_with_response_meta() {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond(Method::Get, "/v1/rates/ticker/", response("[]"));
+        let client = Client::new(mock.clone());
+
+        let (rates, meta) = client.scope(with_response_meta(get_rates("api_key"))).await;
+        assert!(rates.unwrap().is_empty());
+        let meta = meta.unwrap();
+        assert_eq!(meta.status, 200);
+        assert_eq!(meta.body, "[]");
+        assert_eq!(meta.request_id(), Some("req-42"));
+        assert_eq!((meta.rate_limit_remaining(), meta.rate_limit_reset(), meta.rate_limit_limit()), (Some(99), Some(30), None));
+
+        //Unwrapped calls are unaffected
+        assert!(client.scope(get_rates("api_key")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_json_error_keeps_body() {
+        let mock = Arc::new(MockTransport::new());
+        mock.respond(Method::Get, "/v1/rates/ticker/", response(r#"{"unexpected":true}"#));
+        let client = Client::new(mock.clone());
+
+        match client.scope(with_response_meta(get_rates("api_key"))).await {
+            (Err(LNError::JsonError(JsonError { body, .. })), Some(_)) => assert_eq!(body, r#"{"unexpected":true}"#),
+            other => panic!("expected a json error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_meta_of_failed_calls() {
+        let mock = Arc::new(MockTransport::new());
+        let mut limited = response("Too Many Requests");
+        limited.status = 429;
+        mock.respond(Method::Get, "/v1/rates/ticker/", limited);
+        let client = Client::new(mock.clone());
+
+        let (rates, meta) = client.scope(with_response_meta(get_rates("api_key"))).await;
+        assert!(matches!(rates, Err(LNError::HTTPResponseError(_))));
+        let meta = meta.unwrap();
+        assert_eq!((meta.status, meta.request_id(), meta.rate_limit_reset()), (429, Some("req-42"), Some(30)));
+
+        //Nothing was received
+        let (balances, meta) = client.scope(with_response_meta(get_balances("api_key"))).await;
+        assert!(matches!(balances, Err(LNError::HTTPError(_))));
+        assert_eq!(meta, None);
+    }
+}
@@ -0,0 +1,337 @@
+use crate::errors::LNError;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use crate::time::Instant;
+use crate::transport::{HttpRequest, HttpResponse, Method, Transport};
+use crate::types::Invoice;
+#[cfg(feature = "metrics")]
+use std::collections::HashMap;
+#[cfg(feature = "metrics")]
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
+
+// Every request sent by a Client passes through here. With the tracing feature each one
+// gets a "strike.request" span carrying the method, templated path, status, latency and
+// the trace id Strike returns. Paths are templated so ids and handles are not recorded.
+//
+// With the metrics feature the same requests, and the invoices they issue and see paid,
+// are reported through the metrics crate facade to whichever recorder is installed.
+
+pub const REQUESTS_TOTAL: &str = "strike_requests_total";
+pub const REQUEST_DURATION_SECONDS: &str = "strike_request_duration_seconds";
+pub const REQUEST_RETRIES_TOTAL: &str = "strike_request_retries_total";
+pub const RATE_LIMIT_WAIT_SECONDS: &str = "strike_rate_limit_wait_seconds";
+pub const INVOICES_ISSUED_TOTAL: &str = "strike_invoices_issued_total";
+// Both only cover invoices issued by this process within TIME_TO_PAY_HORIZON: invoices
+// issued before a restart, or by another replica, are never counted as paid. Webhooks
+// are the source of truth for payments across processes.
+pub const INVOICES_PAID_TOTAL: &str = "strike_invoices_paid_total";
+pub const INVOICE_TIME_TO_PAY_SECONDS: &str = "strike_invoice_time_to_pay_seconds";
+
+// Issued invoices not yet seen paid are forgotten after this long
+#[cfg(feature = "metrics")]
+const TIME_TO_PAY_HORIZON: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Headers Strike and the proxies in front of it use to identify a request
+const TRACE_ID_HEADERS: &[&str] = &["x-trace-id", "trace-id", "x-request-id", "request-id"];
+
+// Literal segments of Strike API paths; any other segment is an id or a handle
+const STATIC_SEGMENTS: &[&str] = &[
+    "accounts",
+    "balances",
+    "cancel",
+    "currency-exchange-quotes",
+    "execute",
+    "handle",
+    "invoices",
+    "lightning",
+    "lnurl",
+    "onchain",
+    "payment-quotes",
+    "payments",
+    "profile",
+    "quote",
+    "rates",
+    "receive-requests",
+    "receives",
+    "subscriptions",
+    "ticker",
+    "tiers",
+    ".well-known",
+    "lnurlp",
+    "connect",
+    "token",
+];
+
+// "/v1/invoices/handle/magog" -> "/v1/invoices/handle/{handle}"
+pub fn template_path(path: &str) -> String {
+    let mut templated = Vec::new();
+    let mut previous = "";
+    for (index, segment) in path.split('/').enumerate() {
+        let is_version = index == 1 && segment.starts_with('v') && segment[1..].bytes().all(|c| c.is_ascii_digit());
+        templated.push(if segment.is_empty() || is_version || STATIC_SEGMENTS.contains(&segment) {
+            segment
+        } else if previous == "handle" {
+            "{handle}"
+        } else {
+            "{id}"
+        });
+        previous = segment;
+    }
+    templated.join("/")
+}
+
+pub fn trace_id(response: &HttpResponse) -> Option<&str> {
+    find_trace_id(&response.headers)
+}
+
+pub(crate) fn find_trace_id(headers: &[(String, String)]) -> Option<&str> {
+    TRACE_ID_HEADERS.iter().find_map(|name| {
+        headers
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    })
+}
+
+pub(crate) async fn send_observed(transport: &dyn Transport, request: HttpRequest) -> Result<HttpResponse, LNError> {
+    let (method, endpoint) = (request.method, template_path(request.path()));
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    let started = Instant::now();
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "strike.request",
+        method = %request.method,
+        path = %endpoint,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        trace_id = tracing::field::Empty,
+    );
+
+    #[cfg(feature = "tracing")]
+    let result = transport.send(request).instrument(span.clone()).await;
+    #[cfg(not(feature = "tracing"))]
+    let result = transport.send(request).await;
+
+    #[cfg(feature = "tracing")]
+    {
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        let _entered = span.enter();
+        match &result {
+            Ok(response) => {
+                span.record("status", response.status);
+                if let Some(trace_id) = trace_id(response) {
+                    span.record("trace_id", trace_id);
+                }
+                if response.is_success() {
+                    tracing::debug!(status = response.status, "strike request completed");
+                } else {
+                    tracing::warn!(status = response.status, "strike request failed");
+                }
+            }
+            Err(err) => tracing::warn!(error = %err, "strike request could not be sent"),
+        }
+    }
+    #[cfg(feature = "metrics")]
+    record_request(method, &endpoint, result.as_ref().ok().map(|response| response.status), started.elapsed());
+    #[cfg(not(feature = "metrics"))]
+    let _ = (method, endpoint);
+    result
+}
+
+#[cfg(feature = "metrics")]
+fn record_request(method: Method, endpoint: &str, status: Option<u16>, latency: Duration) {
+    let status = status.map(|status| status.to_string()).unwrap_or_else(|| String::from("error"));
+    metrics::counter!(REQUESTS_TOTAL, "method" => method.as_str(), "endpoint" => endpoint.to_string(), "status" => status)
+        .increment(1);
+    metrics::histogram!(REQUEST_DURATION_SECONDS, "method" => method.as_str(), "endpoint" => endpoint.to_string())
+        .record(latency.as_secs_f64());
+}
+
+pub(crate) fn record_retry(method: Method, path: &str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(REQUEST_RETRIES_TOTAL, "method" => method.as_str(), "endpoint" => template_path(path)).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (method, path);
+}
+
+pub(crate) fn record_rate_limit_wait(group: &str, delay: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics::histogram!(RATE_LIMIT_WAIT_SECONDS, "group" => group.to_string()).record(delay.as_secs_f64());
+    #[cfg(not(feature = "metrics"))]
+    let _ = (group, delay);
+}
+
+#[cfg(feature = "metrics")]
+static ISSUED: Mutex<Option<HashMap<String, Instant>>> = Mutex::new(None);
+
+// Placeholders from a dry run are not counted
+pub(crate) fn record_invoice_issued(invoice: &Invoice) {
+    #[cfg(feature = "metrics")]
+    if !crate::client::Client::current().is_dry_run() {
+        metrics::counter!(INVOICES_ISSUED_TOTAL, "currency" => invoice.amount.currency.clone()).increment(1);
+        let mut issued = ISSUED.lock().unwrap();
+        let issued = issued.get_or_insert_with(HashMap::new);
+        issued.retain(|_, at| at.elapsed() < TIME_TO_PAY_HORIZON);
+        issued.insert(invoice.invoice_id.clone(), Instant::now());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = invoice;
+}
+
+// Counts an invoice as paid the first time it is seen PAID after being issued here
+pub(crate) fn record_invoice_seen(invoice: &Invoice) {
+    #[cfg(feature = "metrics")]
+    if invoice.state == "PAID" {
+        let issued_at = ISSUED
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|issued| issued.remove(&invoice.invoice_id));
+        if let Some(issued_at) = issued_at {
+            let currency = invoice.amount.currency.clone();
+            metrics::counter!(INVOICES_PAID_TOTAL, "currency" => currency.clone()).increment(1);
+            metrics::histogram!(INVOICE_TIME_TO_PAY_SECONDS, "currency" => currency).record(issued_at.elapsed().as_secs_f64());
+        }
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = invoice;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_path() {
+        assert_eq!(template_path("/v1/invoices/handle/magog/"), "/v1/invoices/handle/{handle}/");
+        assert_eq!(
+            template_path("/v1/invoices/6ac1bd3e-30a5-4b8c-9b3c-8e1f2a6f0f33/quote"),
+            "/v1/invoices/{id}/quote"
+        );
+        assert_eq!(template_path("/v1/rates/ticker/"), "/v1/rates/ticker/");
+        assert_eq!(template_path("/.well-known/lnurlp/alice"), "/.well-known/lnurlp/{id}");
+    }
+
+    #[test]
+    fn test_trace_id() {
+        let mut response = HttpResponse::new(200, "{}");
+        assert_eq!(trace_id(&response), None);
+        response.headers.push((String::from("X-Request-Id"), String::from("abc")));
+        assert_eq!(trace_id(&response), Some("abc"));
+    }
+
+    #[cfg(feature = "metrics")]
+    mod metrics_recording {
+        use super::super::*;
+        use crate::types::Amount;
+        use metrics::{Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+        use std::sync::Arc;
+
+        // Records every update as "name{label=value,...}" so tests can assert on them
+        #[derive(Default)]
+        struct Events(Mutex<Vec<String>>);
+
+        struct Handle(Arc<Events>, String);
+
+        impl CounterFn for Handle {
+            fn increment(&self, value: u64) {
+                self.0 .0.lock().unwrap().push(format!("{} {}", self.1, value));
+            }
+
+            fn absolute(&self, value: u64) {
+                self.increment(value)
+            }
+        }
+
+        impl HistogramFn for Handle {
+            fn record(&self, _value: f64) {
+                self.0 .0.lock().unwrap().push(format!("{} observed", self.1));
+            }
+        }
+
+        struct TestRecorder(Arc<Events>);
+
+        impl TestRecorder {
+            fn handle(&self, key: &Key) -> Arc<Handle> {
+                let labels: Vec<String> = key.labels().map(|label| format!("{}={}", label.key(), label.value())).collect();
+                Arc::new(Handle(self.0.clone(), format!("{}{{{}}}", key.name(), labels.join(","))))
+            }
+
+            fn events(&self) -> Vec<String> {
+                self.0 .0.lock().unwrap().clone()
+            }
+        }
+
+        impl Recorder for TestRecorder {
+            fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+            fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+                Counter::from_arc(self.handle(key))
+            }
+
+            fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+                Gauge::noop()
+            }
+
+            fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+                Histogram::from_arc(self.handle(key))
+            }
+        }
+
+        #[test]
+        fn test_request_metrics() {
+            let recorder = TestRecorder(Arc::default());
+            metrics::with_local_recorder(&recorder, || {
+                record_request(Method::Get, "/v1/invoices/{id}", Some(404), Duration::from_millis(5));
+                record_request(Method::Post, "/v1/invoices/handle/{handle}/", None, Duration::from_millis(5));
+                record_rate_limit_wait("rates", Duration::from_millis(20));
+                record_retry(Method::Get, "/v1/invoices/6ac1bd3e");
+            });
+            assert_eq!(
+                recorder.events(),
+                vec![
+                    "strike_requests_total{method=GET,endpoint=/v1/invoices/{id},status=404} 1",
+                    "strike_request_duration_seconds{method=GET,endpoint=/v1/invoices/{id}} observed",
+                    "strike_requests_total{method=POST,endpoint=/v1/invoices/handle/{handle}/,status=error} 1",
+                    "strike_request_duration_seconds{method=POST,endpoint=/v1/invoices/handle/{handle}/} observed",
+                    "strike_rate_limit_wait_seconds{group=rates} observed",
+                    "strike_request_retries_total{method=GET,endpoint=/v1/invoices/{id}} 1",
+                ]
+            );
+        }
+
+        #[test]
+        fn test_invoice_metrics() {
+            let mut invoice = Invoice {
+                invoice_id: String::from("metrics-test-invoice"),
+                amount: Amount {
+                    amount: String::from("1.00"),
+                    currency: String::from("USD"),
+                },
+                state: String::from("UNPAID"),
+                ..Default::default()
+            };
+            let recorder = TestRecorder(Arc::default());
+            metrics::with_local_recorder(&recorder, || {
+                record_invoice_issued(&invoice);
+                record_invoice_seen(&invoice);
+                invoice.state = String::from("PAID");
+                record_invoice_seen(&invoice);
+                //Only the first sighting of a paid invoice counts
+                record_invoice_seen(&invoice);
+            });
+            assert_eq!(
+                recorder.events(),
+                vec![
+                    "strike_invoices_issued_total{currency=USD} 1",
+                    "strike_invoices_paid_total{currency=USD} 1",
+                    "strike_invoice_time_to_pay_seconds{currency=USD} observed",
+                ]
+            );
+        }
+    }
+}
@@ -3,6 +3,5 @@ use std::env;
 
 pub fn get_api_key() -> String {
     dotenv::dotenv().ok();
-    let api_key = env::var("API_KEY").unwrap_or("".to_string());
-    return api_key;
-}
\ No newline at end of file
+    env::var("API_KEY").unwrap_or("".to_string())
+}
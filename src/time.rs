@@ -0,0 +1,45 @@
+// std::time::Instant and SystemTime panic on wasm32-unknown-unknown, where web-time
+// provides the same types on top of the browser's clocks.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::errors::LNError;
+use futures_timer::Delay;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+
+// Resolves to whichever future finishes first, dropping the other
+pub(crate) async fn race<T>(first: impl Future<Output = T>, second: impl Future<Output = T>) -> T {
+    let (mut first, mut second) = (pin!(first), pin!(second));
+    poll_fn(|cx| match first.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(output),
+        Poll::Pending => second.as_mut().poll(cx),
+    })
+    .await
+}
+
+// Fails with LNError::Timeout if future has not finished within limit
+pub async fn timeout<F: Future>(limit: Duration, future: F) -> Result<F::Output, LNError> {
+    race(async { Ok(future.await) }, async {
+        Delay::new(limit).await;
+        Err(LNError::Timeout(format!("timed out after {:?}", limit)))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeout() {
+        assert_eq!(timeout(Duration::from_secs(1), async { 7 }).await, Ok(7));
+        let slow = timeout(Duration::from_millis(10), Delay::new(Duration::from_secs(5))).await;
+        assert_eq!(slow, Err(LNError::Timeout(String::from("timed out after 10ms"))));
+    }
+}
@@ -2,6 +2,7 @@ use crate::errors;
 use crate::requests::invoices;
 use crate::requests::quote;
 use crate::types;
+use crate::validation::{ValidationError, Validator, MAX_DESCRIPTION_LENGTH};
 
 impl<'a> From<(&'a TippingRequest<'a>, &'a types::Invoice)> for quote::QuoteRequest<'a> {
     fn from((tipping_request, invoice): (&'a TippingRequest, &'a types::Invoice)) -> Self {
@@ -52,7 +53,7 @@ where
         TippingRequest {
             api_key: api_key.into(),
             account_handle: account_handle.into(),
-            amount: amount,
+            amount,
             currency: currency.into(),
             description: "Tip",
             environment: "api.strike.me",
@@ -70,7 +71,7 @@ where
         TippingRequest {
             api_key: api_key.into(),
             account_handle: account_handle.into(),
-            amount: amount,
+            amount,
             currency: currency.into(),
             description: description.into(),
             environment: "api.strike.me",
@@ -97,7 +98,7 @@ where
         TippingRequest {
             api_key: api_key.into(),
             account_handle: account_handle.into(),
-            amount: amount,
+            amount,
             currency: currency.into(),
             description: description.into(),
             environment: "api.strike.me",
@@ -127,7 +128,7 @@ where
         TippingRequest {
             api_key: api_key.into(),
             account_handle: account_handle.into(),
-            amount: amount,
+            amount,
             currency: currency.into(),
             description: description.into(),
             environment: environment.into(),
@@ -137,11 +138,27 @@ where
     }
 }
 
+impl<'a> TippingRequest<'a> {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut validator = Validator::new();
+        validator
+            .not_empty("account_handle", self.account_handle)
+            .max_length("description", self.description, MAX_DESCRIPTION_LENGTH);
+        if !self.amount.is_finite() || self.amount <= 0.0 {
+            validator.error("amount", "must be a positive number");
+        } else {
+            validator.amount_in("amount", &self.amount.to_string(), self.currency);
+        }
+        validator.finish()
+    }
+}
+
 pub async fn tipping_request<'a, A>(tipping_request: A) -> Result<types::Quote, errors::LNError>
 where
     A: Into<TippingRequest<'a>>,
 {
     let tipping_request = tipping_request.into();
+    tipping_request.validate()?;
     let invoice = invoices::issue::issue_invoice(&tipping_request).await?;
     let quote = quote::request_quote((&tipping_request, &invoice)).await?;
     Ok(quote)
@@ -165,6 +182,19 @@ mod tests {
             "Description",
         ))
         .await;
-        println!("{:?}", quote);
+        assert!(quote.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_tip_is_validated_before_sending() {
+        let tip = tipping_request(("api_key", "", -1.0, "USD", "Description")).await;
+        match tip {
+            Err(errors::LNError::ValidationError(err)) => assert_eq!(err.fields(), vec!["account_handle", "amount"]),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        let tip = TippingRequest::from(("api_key", "magog", 1.005, "USD"));
+        assert_eq!(tip.validate().unwrap_err().fields(), vec!["amount"]);
+        assert!(TippingRequest::from(("api_key", "magog", 1.5, "USD")).validate().is_ok());
     }
 }
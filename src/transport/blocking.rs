@@ -0,0 +1,57 @@
+use crate::client::Timeouts;
+use crate::errors::LNError;
+use crate::transport::reqwest::{configure, reqwest_method, response_headers};
+use crate::transport::{HttpConfig, HttpRequest, HttpResponse, Transport};
+use async_trait::async_trait;
+
+// A synchronous reqwest client. Sending blocks the calling thread, which is what the
+// blocking API wants; it must not be used from inside an async runtime.
+#[derive(Debug, Clone, Default)]
+pub struct BlockingTransport {
+    client: ::reqwest::blocking::Client,
+}
+
+impl BlockingTransport {
+    pub fn new() -> Self {
+        BlockingTransport::default()
+    }
+
+    pub fn from_client(client: ::reqwest::blocking::Client) -> Self {
+        BlockingTransport { client }
+    }
+
+    // A blocked send cannot be interrupted from outside, so the client's own timeout is set
+    // from the attempt timeout, or the total if there is none
+    pub fn with_config(config: &HttpConfig) -> Result<Self, LNError> {
+        let timeouts = &config.timeouts;
+        let builder = ::reqwest::blocking::Client::builder().timeout(timeouts.attempt.or(timeouts.total));
+        let builder = configure!(builder, config);
+        Ok(BlockingTransport::from_client(builder.build()?))
+    }
+
+    pub fn with_timeouts(timeouts: &Timeouts) -> Result<Self, LNError> {
+        BlockingTransport::with_config(&HttpConfig::new().timeouts(*timeouts))
+    }
+
+    pub fn send_blocking(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let mut builder = self.client.request(reqwest_method(request.method), &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder.send().map_err(|e| LNError::HTTPError(e.to_string()))?;
+        let status = response.status().as_u16();
+        let headers = response_headers(response.headers());
+        let body = response.text().map_err(|e| LNError::HTTPError(e.to_string()))?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+#[async_trait]
+impl Transport for BlockingTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        self.send_blocking(request)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::errors::LNError;
+use crate::transport::{HttpRequest, HttpResponse, Method, Transport};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// An in-memory transport with canned responses per method and path. Every request is
+// recorded so tests can check exactly what an endpoint would have sent.
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    routes: Mutex<HashMap<(Method, String), HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    pub fn route(&self, method: Method, path: &str, status: u16, body: &str) -> &Self {
+        self.respond(method, path, HttpResponse::new(status, body))
+    }
+
+    pub fn respond(&self, method: Method, path: &str, response: HttpResponse) -> &Self {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert((method, path.to_string()), response);
+        self
+    }
+
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn last_request(&self) -> Option<HttpRequest> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Transport for MockTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let key = (request.method, request.path().to_string());
+        self.requests.lock().unwrap().push(request);
+        match self.routes.lock().unwrap().get(&key) {
+            Some(response) => Ok(response.clone()),
+            None => Err(LNError::HTTPError(format!("no mock response for {} {}", key.0, key.1))),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::client::Timeouts;
+use crate::errors::LNError;
+use crate::redact::{redact_body, redact_headers};
+use async_trait::async_trait;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+// The boundary between building a request and sending it. Endpoints only produce an
+// HttpRequest and interpret an HttpResponse; a Transport does the IO in between, so the
+// crate can run on any HTTP stack and endpoints can be tested without sockets.
+
+pub mod mock;
+pub mod rate_limit;
+pub mod reqwest;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+#[cfg(feature = "tower")]
+pub mod tower;
+
+pub use self::mock::MockTransport;
+pub use self::rate_limit::{RateLimitedTransport, RateLimits, TokenBucket};
+pub use self::reqwest::ReqwestTransport;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub use self::blocking::BlockingTransport;
+#[cfg(feature = "tower")]
+pub use self::tower::{TowerTransport, TransportService};
+
+// Connection options for the reqwest transports. On wasm the browser owns the connection,
+// so only the timeouts the client enforces itself apply there.
+//
+//     let config = HttpConfig::new()
+//         .proxy("http://egress.internal:3128")
+//         .root_certificate(include_bytes!("corporate-ca.pem").to_vec());
+//     let client = Client::new(ReqwestTransport::with_config(&config)?);
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpConfig {
+    pub timeouts: Timeouts,
+    // Used for both http and https requests
+    pub proxy: Option<String>,
+    // PEM encoded, trusted alongside the TLS backend's own roots
+    pub root_certificates: Vec<Vec<u8>>,
+}
+
+impl HttpConfig {
+    pub fn new() -> Self {
+        HttpConfig::default()
+    }
+
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn proxy<S: Into<String>>(mut self, proxy: S) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn root_certificate(mut self, pem: Vec<u8>) -> Self {
+        self.root_certificates.push(pem);
+        self
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Patch,
+    Delete,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new<S: Into<String>>(method: Method, url: S) -> Self {
+        HttpRequest {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    // Replaces any header of the same name
+    pub fn set_header<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        let name = name.into();
+        self.headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+    }
+
+    // The path of the url without scheme, host or query, e.g. /v1/rates/ticker
+    pub fn path(&self) -> &str {
+        let rest = self.url.split_once("://").map(|(_, rest)| rest).unwrap_or(&self.url);
+        let path = rest.find('/').map(|start| &rest[start..]).unwrap_or("/");
+        path.split(['?', '#']).next().unwrap_or(path)
+    }
+}
+
+// Credentials in headers and body are redacted
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &redact_headers(&self.headers))
+            .field("body", &self.body.as_deref().map(redact_body))
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new<S: Into<String>>(status: u16, body: S) -> Self {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+// Sends a request and returns whatever the server answered. Only failures to get an
+// answer at all are errors here; statuses are left to the caller.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait Transport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        (**self).send(request).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        (**self).send(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_path_and_headers() {
+        let mut request = HttpRequest::new(Method::Get, "https://api.strike.me/v1/invoices/?$top=1");
+        assert_eq!(request.path(), "/v1/invoices/");
+        assert_eq!(HttpRequest::new(Method::Get, "http://127.0.0.1:80").path(), "/");
+
+        request.set_header("Accept", "text/plain");
+        request.set_header("accept", "application/json");
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(request.header("ACCEPT"), Some("application/json"));
+    }
+}
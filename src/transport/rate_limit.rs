@@ -0,0 +1,309 @@
+use crate::errors::LNError;
+use crate::telemetry::record_rate_limit_wait;
+use crate::time::Instant;
+use crate::transport::{HttpRequest, HttpResponse, Transport};
+use async_trait::async_trait;
+use futures_timer::Delay;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+// Keeps a client under Strike's per key rate limits by queueing requests before they are
+// sent. Each endpoint group (the first path segment after the api version, such as
+// "rates", "invoices" or "subscriptions") draws from its own token bucket, and an optional
+// cap bounds how many requests are in flight at once across all groups.
+//
+//     let limits = RateLimits::new()
+//         .group("invoices", TokenBucket::new(10, Duration::from_secs(1)))
+//         .max_in_flight(4);
+//     let client = Client::new(RateLimitedTransport::new(ReqwestTransport::new(), limits));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenBucket {
+    pub capacity: u32,
+    pub refill_per_second: f64,
+}
+
+impl TokenBucket {
+    // Allows bursts of up to requests, refilled evenly over per
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let capacity = requests.max(1);
+        TokenBucket {
+            capacity,
+            refill_per_second: capacity as f64 / per.as_secs_f64().max(0.001),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RateLimits {
+    groups: HashMap<String, TokenBucket>,
+    default: Option<TokenBucket>,
+    max_in_flight: Option<usize>,
+}
+
+impl RateLimits {
+    pub fn new() -> Self {
+        RateLimits::default()
+    }
+
+    pub fn group(mut self, group: &str, bucket: TokenBucket) -> Self {
+        self.groups.insert(group.to_string(), bucket);
+        self
+    }
+
+    // For groups without a limit of their own; each group still gets a separate bucket
+    pub fn default_limit(mut self, bucket: TokenBucket) -> Self {
+        self.default = Some(bucket);
+        self
+    }
+
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+}
+
+// Time spent queued before being sent, whether waiting for a token or an in-flight slot
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueueMetrics {
+    pub requests: u64,
+    pub delayed: u64,
+    pub total_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl QueueMetrics {
+    pub fn average_delay(&self) -> Duration {
+        match self.requests {
+            0 => Duration::ZERO,
+            requests => self.total_delay / requests as u32,
+        }
+    }
+
+    fn record(&mut self, delay: Duration, waited: bool) {
+        self.requests += 1;
+        if waited {
+            self.delayed += 1;
+        }
+        self.total_delay += delay;
+        self.max_delay = self.max_delay.max(delay);
+    }
+
+    fn merge(&mut self, other: &QueueMetrics) {
+        self.requests += other.requests;
+        self.delayed += other.delayed;
+        self.total_delay += other.total_delay;
+        self.max_delay = self.max_delay.max(other.max_delay);
+    }
+}
+
+struct Bucket {
+    config: TokenBucket,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl Bucket {
+    fn new(config: TokenBucket) -> Self {
+        Bucket {
+            config,
+            state: Mutex::new((config.capacity as f64, Instant::now())),
+        }
+    }
+
+    // Takes a token, or says how long until one is available
+    fn try_take(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let refilled = now.duration_since(state.1).as_secs_f64() * self.config.refill_per_second;
+        *state = ((state.0 + refilled).min(self.config.capacity as f64), now);
+        if state.0 >= 1.0 {
+            state.0 -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - state.0) / self.config.refill_per_second))
+        }
+    }
+
+    // Whether the caller had to wait
+    async fn take(&self) -> bool {
+        let mut waited = false;
+        while let Err(wait) = self.try_take() {
+            waited = true;
+            Delay::new(wait).await;
+        }
+        waited
+    }
+}
+
+pub struct RateLimitedTransport<T> {
+    inner: T,
+    limits: RateLimits,
+    buckets: Mutex<HashMap<String, Arc<Bucket>>>,
+    in_flight: Option<Semaphore>,
+    metrics: Mutex<HashMap<String, QueueMetrics>>,
+}
+
+impl<T> RateLimitedTransport<T> {
+    pub fn new(inner: T, limits: RateLimits) -> Self {
+        RateLimitedTransport {
+            inner,
+            in_flight: limits.max_in_flight.map(Semaphore::new),
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            metrics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn metrics(&self) -> QueueMetrics {
+        let mut total = QueueMetrics::default();
+        for metrics in self.metrics.lock().unwrap().values() {
+            total.merge(metrics);
+        }
+        total
+    }
+
+    pub fn group_metrics(&self, group: &str) -> QueueMetrics {
+        self.metrics.lock().unwrap().get(group).copied().unwrap_or_default()
+    }
+
+    fn bucket(&self, group: &str) -> Option<Arc<Bucket>> {
+        let config = self.limits.groups.get(group).or(self.limits.default.as_ref())?;
+        let mut buckets = self.buckets.lock().unwrap();
+        Some(
+            buckets
+                .entry(group.to_string())
+                .or_insert_with(|| Arc::new(Bucket::new(*config)))
+                .clone(),
+        )
+    }
+}
+
+// "/v1/invoices/handle/magog" -> "invoices"
+pub fn endpoint_group(path: &str) -> &str {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let first = segments.next().unwrap_or("");
+    let is_version = first.len() > 1 && first.starts_with('v') && first[1..].bytes().all(|c| c.is_ascii_digit());
+    if is_version {
+        segments.next().unwrap_or("")
+    } else {
+        first
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T: Transport> Transport for RateLimitedTransport<T> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let group = endpoint_group(request.path()).to_string();
+        let queued = Instant::now();
+        let mut waited = false;
+
+        if let Some(bucket) = self.bucket(&group) {
+            waited |= bucket.take().await;
+        }
+        let _permit = match &self.in_flight {
+            Some(semaphore) => match semaphore.try_acquire() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    waited = true;
+                    let permit = semaphore.acquire().await;
+                    Some(permit.map_err(|err| LNError::HTTPError(err.to_string()))?)
+                }
+            },
+            None => None,
+        };
+
+        let delay = if waited { queued.elapsed() } else { Duration::ZERO };
+        if waited {
+            record_rate_limit_wait(&group, delay);
+        }
+        self.metrics.lock().unwrap().entry(group).or_default().record(delay, waited);
+        self.inner.send(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{Method, MockTransport};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_endpoint_group() {
+        assert_eq!(endpoint_group("/v1/invoices/handle/magog"), "invoices");
+        assert_eq!(endpoint_group("/v1/rates/ticker/"), "rates");
+        assert_eq!(endpoint_group("/.well-known/lnurlp/alice"), ".well-known");
+        assert_eq!(endpoint_group("/"), "");
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_queues_per_group() {
+        let mock = MockTransport::new();
+        mock.route(Method::Get, "/v1/rates/ticker/", 200, "[]");
+        mock.route(Method::Get, "/v1/subscriptions/", 200, "[]");
+        let limits = RateLimits::new().group("rates", TokenBucket::new(2, Duration::from_millis(100)));
+        let transport = RateLimitedTransport::new(mock, limits);
+
+        let started = Instant::now();
+        for _ in 0..4 {
+            transport
+                .send(HttpRequest::new(Method::Get, "https://api.strike.me/v1/rates/ticker/"))
+                .await
+                .unwrap();
+        }
+        //A burst of two, then two more at 20 per second
+        assert!(started.elapsed() >= Duration::from_millis(90));
+        transport
+            .send(HttpRequest::new(Method::Get, "https://api.strike.me/v1/subscriptions/"))
+            .await
+            .unwrap();
+
+        let rates = transport.group_metrics("rates");
+        assert_eq!((rates.requests, rates.delayed), (4, 2));
+        assert!(rates.max_delay >= Duration::from_millis(40));
+        assert_eq!(transport.group_metrics("subscriptions").delayed, 0);
+        assert_eq!(transport.metrics().requests, 5);
+    }
+
+    struct Slow {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Transport for Slow {
+        async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, LNError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            Delay::new(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(HttpResponse::new(200, "[]"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        let slow = Slow {
+            in_flight: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        };
+        let transport = Arc::new(RateLimitedTransport::new(slow, RateLimits::new().max_in_flight(2)));
+
+        let send = |transport: Arc<RateLimitedTransport<Slow>>| async move {
+            transport
+                .send(HttpRequest::new(Method::Get, "https://api.strike.me/v1/invoices/"))
+                .await
+        };
+        let results = tokio::join!(
+            send(transport.clone()),
+            send(transport.clone()),
+            send(transport.clone()),
+            send(transport.clone())
+        );
+        assert!(results.0.is_ok() && results.3.is_ok());
+        assert_eq!(transport.inner.peak.load(Ordering::SeqCst), 2);
+        assert_eq!(transport.group_metrics("invoices").delayed, 2);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::client::Timeouts;
+use crate::errors::LNError;
+use crate::transport::{HttpConfig, HttpRequest, HttpResponse, Method, Transport};
+use async_trait::async_trait;
+
+// The default transport, an async reqwest client shared by every request
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: ::reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        ReqwestTransport::default()
+    }
+
+    pub fn from_client(client: ::reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+
+    // Applies the connect timeout, proxy and root certificates of config
+    pub fn with_config(config: &HttpConfig) -> Result<Self, LNError> {
+        let builder = ::reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = configure!(builder, config);
+        #[cfg(target_arch = "wasm32")]
+        let _ = config;
+        Ok(ReqwestTransport::from_client(builder.build()?))
+    }
+
+    pub fn with_timeouts(timeouts: &Timeouts) -> Result<Self, LNError> {
+        ReqwestTransport::with_config(&HttpConfig::new().timeouts(*timeouts))
+    }
+}
+
+// Applies an HttpConfig to a reqwest ClientBuilder, async or blocking, returning early
+// with an error if the proxy or a certificate is invalid
+macro_rules! configure {
+    ($builder:expr, $config:expr) => {{
+        let (mut builder, config): (_, &$crate::transport::HttpConfig) = ($builder, $config);
+        if let Some(connect) = config.timeouts.connect {
+            builder = builder.connect_timeout(connect);
+        }
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(::reqwest::Proxy::all(proxy)?);
+        }
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        for pem in &config.root_certificates {
+            builder = builder.add_root_certificate($crate::transport::reqwest::root_certificate(pem)?);
+        }
+        #[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+        if !config.root_certificates.is_empty() {
+            return Err($crate::errors::LNError::HTTPError(String::from(
+                "root certificates need the native-tls or rustls feature",
+            )));
+        }
+        builder
+    }};
+}
+pub(crate) use configure;
+
+// rustls skips anything that is not a PEM certificate instead of failing, so check first
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub(crate) fn root_certificate(pem: &[u8]) -> Result<::reqwest::Certificate, LNError> {
+    if !String::from_utf8_lossy(pem).contains("-----BEGIN CERTIFICATE-----") {
+        return Err(LNError::HTTPError(String::from("root certificate is not PEM encoded")));
+    }
+    Ok(::reqwest::Certificate::from_pem(pem)?)
+}
+
+pub(crate) fn reqwest_method(method: Method) -> ::reqwest::Method {
+    match method {
+        Method::Get => ::reqwest::Method::GET,
+        Method::Post => ::reqwest::Method::POST,
+        Method::Patch => ::reqwest::Method::PATCH,
+        Method::Delete => ::reqwest::Method::DELETE,
+    }
+}
+
+pub(crate) fn response_headers(headers: &::reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let mut builder = self.client.request(reqwest_method(request.method), &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder.send().await.map_err(|e| LNError::HTTPError(e.to_string()))?;
+        let status = response.status().as_u16();
+        let headers = response_headers(response.headers());
+        let body = response.text().await.map_err(|e| LNError::HTTPError(e.to_string()))?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        assert!(ReqwestTransport::with_config(&HttpConfig::new().proxy("http://egress.internal:3128")).is_ok());
+        assert!(ReqwestTransport::with_config(&HttpConfig::new().proxy("not a url")).is_err());
+        let certificate = HttpConfig::new().root_certificate(b"not a certificate".to_vec());
+        assert!(ReqwestTransport::with_config(&certificate).is_err());
+    }
+}
+
@@ -0,0 +1,211 @@
+use crate::errors::LNError;
+use crate::transport::{HttpRequest, HttpResponse, Method, Transport};
+use async_trait::async_trait;
+use std::error::Error;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+// Bridges to tower in both directions. TowerTransport sends through any
+// Service<http::Request<String>> stack, and TransportService exposes a Transport as such a
+// Service, so existing layers can be put in front of the crate's own transports:
+//
+//     let stack = ServiceBuilder::new()
+//         .timeout(Duration::from_secs(10))
+//         .service(TransportService::new(ReqwestTransport::new()));
+//     let client = Client::new(TowerTransport::new(stack));
+
+pub fn to_http_request(request: HttpRequest) -> Result<http::Request<String>, LNError> {
+    let mut builder = http::Request::builder()
+        .method(request.method.as_str())
+        .uri(&request.url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(request.body.unwrap_or_default())
+        .map_err(|err| LNError::HTTPError(err.to_string()))
+}
+
+pub fn from_http_request(request: http::Request<String>) -> Result<HttpRequest, LNError> {
+    let method = match *request.method() {
+        http::Method::GET => Method::Get,
+        http::Method::POST => Method::Post,
+        http::Method::PATCH => Method::Patch,
+        http::Method::DELETE => Method::Delete,
+        ref other => return Err(LNError::HTTPError(format!("unsupported method {}", other))),
+    };
+    let mut converted = HttpRequest::new(method, request.uri().to_string());
+    converted.headers = header_pairs(request.headers());
+    let body = request.into_body();
+    if !body.is_empty() {
+        converted.body = Some(body);
+    }
+    Ok(converted)
+}
+
+pub fn to_http_response(response: HttpResponse) -> Result<http::Response<String>, LNError> {
+    let mut builder = http::Response::builder().status(response.status);
+    for (name, value) in &response.headers {
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(response.body)
+        .map_err(|err| LNError::HTTPError(err.to_string()))
+}
+
+pub fn from_http_response<B: Into<Vec<u8>>>(response: http::Response<B>) -> HttpResponse {
+    let status = response.status().as_u16();
+    let headers = header_pairs(response.headers());
+    let body = response.into_body().into();
+    HttpResponse {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }
+}
+
+fn header_pairs(headers: &http::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+// Errors from layers such as timeouts are boxed; an LNError from further down is kept as is
+fn into_ln_error<E: Into<Box<dyn Error + Send + Sync>>>(err: E) -> LNError {
+    match err.into().downcast::<LNError>() {
+        Ok(err) => *err,
+        Err(err) => LNError::HTTPError(err.to_string()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TowerTransport<S> {
+    service: S,
+}
+
+impl<S> TowerTransport<S> {
+    pub fn new(service: S) -> Self {
+        TowerTransport { service }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<S, B> Transport for TowerTransport<S>
+where
+    S: Service<http::Request<String>, Response = http::Response<B>> + Clone + Send + Sync,
+    S::Error: Into<Box<dyn Error + Send + Sync>>,
+    S::Future: Send,
+    B: Into<Vec<u8>>,
+{
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, LNError> {
+        let request = to_http_request(request)?;
+        let mut service = self.service.clone();
+        poll_fn(|cx| service.poll_ready(cx)).await.map_err(into_ln_error)?;
+        let response = service.call(request).await.map_err(into_ln_error)?;
+        Ok(from_http_response(response))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type ServiceFuture = Pin<Box<dyn Future<Output = Result<http::Response<String>, LNError>> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type ServiceFuture = Pin<Box<dyn Future<Output = Result<http::Response<String>, LNError>>>>;
+
+#[derive(Debug, Clone)]
+pub struct TransportService<T> {
+    transport: std::sync::Arc<T>,
+}
+
+impl<T> TransportService<T> {
+    pub fn new(transport: T) -> Self {
+        TransportService {
+            transport: std::sync::Arc::new(transport),
+        }
+    }
+}
+
+impl<T: Transport + 'static> Service<http::Request<String>> for TransportService<T> {
+    type Response = http::Response<String>;
+    type Error = LNError;
+    type Future = ServiceFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<String>) -> Self::Future {
+        let transport = self.transport.clone();
+        Box::pin(async move {
+            let response = transport.send(from_http_request(request)?).await?;
+            to_http_response(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::requests::rates::{get_rates, RatesRequest};
+    use crate::transport::MockTransport;
+    use std::sync::Arc;
+
+    // A minimal layer: stamps every request with a header
+    #[derive(Clone)]
+    struct Stamp<S>(S);
+
+    impl<S: Service<http::Request<String>>> Service<http::Request<String>> for Stamp<S> {
+        type Response = S::Response;
+        type Error = S::Error;
+        type Future = S::Future;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.0.poll_ready(cx)
+        }
+
+        fn call(&mut self, mut request: http::Request<String>) -> Self::Future {
+            request.headers_mut().insert("x-stamp", http::HeaderValue::from_static("layered"));
+            self.0.call(request)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layers_wrap_requests() {
+        let mock = Arc::new(MockTransport::new());
+        mock.route(Method::Get, "/v1/rates/ticker/", 200, "[]");
+        let client = Client::new(TowerTransport::new(Stamp(TransportService::new(mock.clone()))));
+
+        assert_eq!(client.scope(get_rates("api_key")).await, Ok(vec![]));
+        let request = mock.last_request().unwrap();
+        assert_eq!(request.header("x-stamp"), Some("layered"));
+        assert_eq!(request.header("authorization"), Some("Bearer api_key"));
+        assert_eq!(request.url, "https://api.strike.me/v1/rates/ticker/");
+
+        //Errors from the inner transport come back unchanged
+        let missing = client.scope(get_rates(RatesRequest {
+                api_key: "api_key",
+                environment: "api.strike.me",
+                api_version: "v2",
+            })).await;
+        assert!(matches!(missing, Err(LNError::HTTPError(message)) if message.starts_with("no mock response")));
+    }
+
+    #[test]
+    fn test_conversions_round_trip() {
+        let mut request = HttpRequest::new(Method::Post, "https://api.strike.me/v1/invoices");
+        request.set_header("Content-Type", "application/json");
+        request.body = Some(String::from("{}"));
+        assert_eq!(from_http_request(to_http_request(request.clone()).unwrap()).unwrap(), {
+            let mut expected = request.clone();
+            expected.headers = vec![(String::from("content-type"), String::from("application/json"))];
+            expected
+        });
+
+        let response = HttpResponse::new(201, "{}");
+        assert_eq!(from_http_response(to_http_response(response.clone()).unwrap()), response);
+    }
+}
@@ -1,18 +1,31 @@
 use serde::{Deserialize, Serialize};
-use std::fmt::{Debug};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use crate::redact::REDACTED;
+use std::fmt::{self, Debug, Formatter};
+use std::str::FromStr;
 
+// Fields the API returned that these types do not know about yet, so that additions
+// to the Strike API can be seen without a crate release.
+pub type Extra = HashMap<String, Value>;
+
+// The responses of the request bindings read missing fields as their defaults, so the {}
+// placeholder of a dry run reads as any of them.
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Quote {
     pub quote_id: String,
-    pub description: String,
+    pub description: Option<String>,
     pub ln_invoice: String,
     pub expiration: String,
     pub expiration_in_sec: i64,
     pub source_amount: SourceAmount,
     pub target_amount: TargetAmount,
     pub conversion_rate: ConversionRate,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -38,22 +51,25 @@ pub struct ConversionRate {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Invoices {
     pub items: Vec<Invoice>,
     pub count: i64,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Invoice {
     pub invoice_id: String,
     pub amount: Amount,
     pub state: String,
     pub created: String,
-    pub description: String,
+    pub description: Option<String>,
+    pub correlation_id: Option<String>,
     pub issuer_id: String,
     pub receiver_id: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -63,8 +79,8 @@ pub struct Amount {
     pub amount: String,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct Subscription {
     pub id: String,
     pub webhook_url: String,
@@ -74,6 +90,24 @@ pub struct Subscription {
     #[serde(skip_deserializing)]
     pub secret: String,
     pub event_types: Vec<Event>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// The webhook secret signs deliveries and is kept out of logs
+impl Debug for Subscription {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .field("webhook_url", &self.webhook_url)
+            .field("webhook_version", &self.webhook_version)
+            .field("enabled", &self.enabled)
+            .field("created", &self.created)
+            .field("secret", &REDACTED)
+            .field("event_types", &self.event_types)
+            .field("extra", &self.extra)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -90,22 +124,21 @@ pub struct Rate {
     pub amount: String,
     pub source_currency: String,
     pub target_currency: String,
-}
-
-fn none_string() -> String {
-    String::from("None")
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Account {
+    pub id: Option<String>,
     pub handle: String,
-    #[serde(default = "none_string")]
-    pub avatar_url: String,
-    #[serde(default = "none_string")]
-    pub description: String,
+    pub avatar_url: Option<String>,
+    pub description: Option<String>,
     pub can_receive: bool,
     pub currencies: Vec<Currency>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -116,6 +149,347 @@ pub struct Currency {
     pub is_available: bool,
 }
 
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CurrencyCode {
+    #[default]
+    Btc,
+    Usd,
+    Eur,
+    Usdt,
+    Gbp,
+    Aud,
+}
+
+impl CurrencyCode {
+    // Decimal places Strike accepts for amounts in this currency
+    pub fn decimals(&self) -> u32 {
+        match self {
+            CurrencyCode::Btc => 8,
+            CurrencyCode::Usdt => 6,
+            _ => 2,
+        }
+    }
+}
+
+impl FromStr for CurrencyCode {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code.to_ascii_uppercase().as_str() {
+            "BTC" => Ok(CurrencyCode::Btc),
+            "USD" => Ok(CurrencyCode::Usd),
+            "EUR" => Ok(CurrencyCode::Eur),
+            "USDT" => Ok(CurrencyCode::Usdt),
+            "GBP" => Ok(CurrencyCode::Gbp),
+            "AUD" => Ok(CurrencyCode::Aud),
+            _ => Err(format!("unknown currency {}", code)),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Money {
+    pub amount: String,
+    pub currency: CurrencyCode,
+}
+
+impl Money {
+    pub fn new<S: Into<String>>(amount: S, currency: CurrencyCode) -> Self {
+        Money {
+            amount: amount.into(),
+            currency,
+        }
+    }
+
+    // Compares the decimal amounts exactly; None if the currencies differ or an amount does not parse
+    pub fn compare(&self, other: &Money) -> Option<Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+        let (a, a_scale) = parse_decimal(&self.amount)?;
+        let (b, b_scale) = parse_decimal(&other.amount)?;
+        let scale = a_scale.max(b_scale);
+        let a = a.checked_mul(10i128.checked_pow(scale - a_scale)?)?;
+        let b = b.checked_mul(10i128.checked_pow(scale - b_scale)?)?;
+        Some(a.cmp(&b))
+    }
+}
+
+// "12.340" -> (12340, 3)
+pub(crate) fn parse_decimal(amount: &str) -> Option<(i128, u32)> {
+    let amount = amount.trim();
+    let (negative, amount) = match amount.strip_prefix('-') {
+        Some(amount) => (true, amount),
+        None => (false, amount),
+    };
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value: i128 = format!("{}{}", whole, fraction).parse().ok()?;
+    Some((if negative { -value } else { value }, fraction.len() as u32))
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReceiveRequest {
+    pub receive_request_id: String,
+    pub created: String,
+    pub target_currency: Option<CurrencyCode>,
+    pub bolt11: Option<Bolt11ReceiveRequest>,
+    pub onchain: Option<OnchainReceiveRequest>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bolt11ReceiveRequest {
+    pub invoice: String,
+    pub requested_amount: Option<Money>,
+    pub btc_amount: Option<String>,
+    pub description: Option<String>,
+    pub description_hash: Option<String>,
+    pub payment_hash: String,
+    pub expires: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainReceiveRequest {
+    pub address: String,
+    pub amount: Option<Money>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ReceiveType {
+    #[default]
+    Lightning,
+    Onchain,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ReceiveState {
+    #[default]
+    Pending,
+    Completed,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receive {
+    pub receive_id: String,
+    pub receive_request_id: String,
+    #[serde(rename = "type")]
+    pub receive_type: ReceiveType,
+    pub state: ReceiveState,
+    pub amount_received: Money,
+    pub amount_credited: Option<Money>,
+    pub created: String,
+    pub completed: Option<String>,
+    pub lightning: Option<LightningReceive>,
+    pub onchain: Option<OnchainReceive>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LightningReceive {
+    pub invoice: String,
+    pub preimage: Option<String>,
+    pub description: Option<String>,
+    pub description_hash: Option<String>,
+    pub payment_hash: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainReceive {
+    pub address: String,
+    pub tx_id: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Receives {
+    pub items: Vec<Receive>,
+    pub count: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PaymentQuote {
+    pub payment_quote_id: String,
+    pub description: Option<String>,
+    pub valid_until: String,
+    pub conversion_rate: Option<ConversionRate>,
+    pub amount: Money,
+    pub lightning_network_fee: Option<Money>,
+    pub onchain_fee: Option<Money>,
+    pub estimated_delivery_duration_in_min: Option<u32>,
+    pub total_amount: Money,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnchainTier {
+    pub id: String,
+    pub estimated_delivery_duration_in_min: u32,
+    pub estimated_fee: Money,
+    pub total_amount: Money,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PaymentState {
+    #[default]
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl PaymentState {
+    // Completed and Failed payments will not change state again
+    pub fn is_final(&self) -> bool {
+        !matches!(self, PaymentState::Pending)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Payment {
+    pub payment_id: String,
+    pub state: PaymentState,
+    pub result: Option<String>,
+    pub completed: Option<String>,
+    pub conversion_rate: Option<ConversionRate>,
+    pub amount: Money,
+    pub lightning_network_fee: Option<Money>,
+    pub onchain_fee: Option<Money>,
+    pub total_amount: Money,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FeePolicy {
+    #[default]
+    Inclusive,
+    Exclusive,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CurrencyExchangeState {
+    #[default]
+    New,
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl CurrencyExchangeState {
+    // Completed and Failed exchanges will not change state again
+    pub fn is_final(&self) -> bool {
+        matches!(self, CurrencyExchangeState::Completed | CurrencyExchangeState::Failed)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CurrencyExchangeQuote {
+    pub id: String,
+    pub created: String,
+    pub valid_until: String,
+    pub source: Money,
+    pub target: Money,
+    pub fee: Option<Money>,
+    pub conversion_rate: ConversionRate,
+    pub state: CurrencyExchangeState,
+    pub completed: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "BalanceData", into = "BalanceData")]
+pub struct Balance {
+    pub currency: CurrencyCode,
+    pub current: Money,
+    pub pending: Money,
+    pub outgoing: Money,
+    pub reserved: Money,
+    pub available: Money,
+    pub total: Money,
+    // Read and written through the flattened extra of BalanceData
+    pub extra: Extra,
+}
+
+impl Balance {
+    pub fn can_afford(&self, amount: &Money) -> bool {
+        matches!(self.available.compare(amount), Some(Ordering::Greater | Ordering::Equal))
+    }
+}
+
+// The wire format of a balance, with a single currency for all amounts
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BalanceData {
+    currency: CurrencyCode,
+    current: String,
+    pending: String,
+    outgoing: String,
+    reserved: String,
+    available: String,
+    total: String,
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+impl From<BalanceData> for Balance {
+    fn from(data: BalanceData) -> Self {
+        let money = |amount: String| Money::new(amount, data.currency);
+        Balance {
+            currency: data.currency,
+            current: money(data.current),
+            pending: money(data.pending),
+            outgoing: money(data.outgoing),
+            reserved: money(data.reserved),
+            available: money(data.available),
+            total: money(data.total),
+            extra: data.extra,
+        }
+    }
+}
+
+impl From<Balance> for BalanceData {
+    fn from(balance: Balance) -> Self {
+        BalanceData {
+            currency: balance.currency,
+            current: balance.current.amount,
+            pending: balance.pending.amount,
+            outgoing: balance.outgoing.amount,
+            reserved: balance.reserved.amount,
+            available: balance.available.amount,
+            total: balance.total.amount,
+            extra: balance.extra,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,7 +522,44 @@ mod tests {
                 secret: "".to_string(),
                 created: "2022-02-23T18:29:18.773+00:00".to_string(),
                 event_types: vec![Event::InvoiceCreated, Event::InvoiceUpdated],
+                extra: Extra::new(),
             }
         );
     }
+
+    #[test]
+    fn test_deserialize_account_optional_fields() {
+        let account: Account = serde_json::from_str(
+            r#"{
+                "id": "bf909224-3432-400b-895a-3010302f80f5",
+                "handle": "magog",
+                "canReceive": true,
+                "currencies": [
+                  { "currency": "USD", "isDefaultCurrency": true, "isAvailable": true }
+                ],
+                "isVerified": true
+              }"#,
+        )
+        .unwrap();
+
+        assert_eq!(account.avatar_url, None);
+        assert_eq!(account.description, None);
+        assert_eq!(account.extra.get("isVerified"), Some(&Value::Bool(true)));
+        assert_eq!(account.extra.len(), 1);
+
+        let round_trip: Account = serde_json::from_str(&serde_json::to_string(&account).unwrap()).unwrap();
+        assert_eq!(round_trip, account);
+    }
+
+    #[test]
+    fn test_compare_money() {
+        let usd = |amount: &str| Money::new(amount, CurrencyCode::Usd);
+        assert_eq!(usd("1.5").compare(&usd("1.50")), Some(Ordering::Equal));
+        assert_eq!(usd("10").compare(&usd("9.99")), Some(Ordering::Greater));
+        assert_eq!(usd(".5").compare(&usd("0.51")), Some(Ordering::Less));
+        assert_eq!(usd("-1").compare(&usd("0")), Some(Ordering::Less));
+        assert_eq!(usd("1").compare(&Money::new("1", CurrencyCode::Eur)), None);
+        assert_eq!(usd("1,00").compare(&usd("1")), None);
+        assert_eq!(usd("").compare(&usd("1")), None);
+    }
 }
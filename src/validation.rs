@@ -0,0 +1,176 @@
+use crate::address::{validate_address, Network};
+use crate::errors::LNError;
+use crate::types::{parse_decimal, CurrencyCode};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+// Checks run on a request before it is sent. Every offending field is collected so that
+// a caller sees all of their mistakes at once instead of one 400 at a time.
+
+pub const MAX_DESCRIPTION_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationError {
+    pub fn fields(&self) -> Vec<&str> {
+        self.errors.iter().map(|error| error.field.as_str()).collect()
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect();
+        write!(f, "invalid request: {}", errors.join(", "))
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    pub fn error(&mut self, field: &str, message: &str) -> &mut Self {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
+    pub fn not_empty(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.error(field, "must not be empty");
+        }
+        self
+    }
+
+    pub fn max_length(&mut self, field: &str, value: &str, max: usize) -> &mut Self {
+        if value.chars().count() > max {
+            self.error(field, &format!("must be at most {} characters", max));
+        }
+        self
+    }
+
+    pub fn https_url(&mut self, field: &str, value: &str) -> &mut Self {
+        let host = value
+            .get(..8)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("https://"))
+            .map(|_| &value[8..]);
+        match host {
+            Some(host) if !host.is_empty() && !host.contains(char::is_whitespace) => {}
+            _ => {
+                self.error(field, "must be an https url");
+            }
+        }
+        self
+    }
+
+    // A positive decimal with no more places than the currency allows
+    pub fn amount(&mut self, field: &str, amount: &str, currency: CurrencyCode) -> &mut Self {
+        match parse_decimal(amount) {
+            None => {
+                self.error(field, "must be a decimal number");
+            }
+            Some((value, _)) if value <= 0 => {
+                self.error(field, "must be greater than zero");
+            }
+            Some((_, scale)) if scale > currency.decimals() => {
+                self.error(
+                    field,
+                    &format!("must have at most {} decimal places for {:?}", currency.decimals(), currency),
+                );
+            }
+            _ => {}
+        }
+        self
+    }
+
+    // As amount, for the string currency codes used by invoices
+    pub fn amount_in(&mut self, field: &str, amount: &str, currency: &str) -> &mut Self {
+        match CurrencyCode::from_str(currency) {
+            Ok(currency) => self.amount(field, amount, currency),
+            Err(_) => self.error(field, &format!("unknown currency {}", currency)),
+        }
+    }
+
+    // A bitcoin address for network, as checked by address::validate_address
+    pub fn btc_address(&mut self, field: &str, address: &str, network: Network) -> &mut Self {
+        match validate_address(address, network) {
+            Err(LNError::InvalidAddress(reason)) => self.error(field, &reason),
+            Err(err) => self.error(field, &err.to_string()),
+            Ok(_) => self,
+        }
+    }
+
+    pub fn finish(&mut self) -> Result<(), ValidationError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError {
+                errors: std::mem::take(&mut self.errors),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_collects_every_field() {
+        let error = Validator::new()
+            .not_empty("account_handle", " ")
+            .https_url("webhook_url", "http://example.com")
+            .max_length("description", &"a".repeat(MAX_DESCRIPTION_LENGTH + 1), MAX_DESCRIPTION_LENGTH)
+            .amount("amount", "-1", CurrencyCode::Usd)
+            .finish()
+            .unwrap_err();
+        assert_eq!(error.fields(), vec!["account_handle", "webhook_url", "description", "amount"]);
+    }
+
+    #[test]
+    fn test_validator_amounts() {
+        assert!(Validator::new().amount("amount", "1.25", CurrencyCode::Usd).finish().is_ok());
+        assert!(Validator::new().amount("amount", "0.00000001", CurrencyCode::Btc).finish().is_ok());
+        assert!(Validator::new().amount("amount", "1.255", CurrencyCode::Usd).finish().is_err());
+        assert!(Validator::new().amount("amount", "0", CurrencyCode::Usd).finish().is_err());
+        assert!(Validator::new().amount("amount", "NaN", CurrencyCode::Usd).finish().is_err());
+        assert!(Validator::new().amount_in("amount", "1.00", "usd").finish().is_ok());
+        assert!(Validator::new().amount_in("amount", "1.00", "XYZ").finish().is_err());
+    }
+
+    #[test]
+    fn test_validator_https_url() {
+        assert!(Validator::new().https_url("webhook_url", "https://example.com/hook").finish().is_ok());
+        assert!(Validator::new().https_url("webhook_url", "https://").finish().is_err());
+        assert!(Validator::new().https_url("webhook_url", "ftp://example.com").finish().is_err());
+    }
+
+    #[test]
+    fn test_validator_btc_address() {
+        let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert!(Validator::new().btc_address("btc_address", address, Network::Mainnet).finish().is_ok());
+        let error = Validator::new().btc_address("btc_address", address, Network::Testnet).finish().unwrap_err();
+        assert_eq!(error.fields(), vec!["btc_address"]);
+    }
+}
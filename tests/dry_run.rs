@@ -0,0 +1,32 @@
+#![cfg(feature = "base")]
+
+// Making a dry run client the default affects every unscoped request in the process, so
+// this runs in its own binary rather than alongside the unit tests
+use std::sync::Arc;
+
+use strike_api::client::Client;
+use strike_api::requests::rates::get_rates;
+use strike_api::transport::MockTransport;
+
+// Restores the previous default even if the test fails
+struct RestoreDefault(Option<Client>);
+
+impl Drop for RestoreDefault {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            Client::set_default(previous);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_default_covers_spawned_tasks() {
+    let mock = Arc::new(MockTransport::new());
+    let client = Client::new(mock.clone()).with_dry_run();
+    let _restore = RestoreDefault(Some(Client::set_default(client.clone())));
+
+    let rates = client.scope(async { tokio::spawn(get_rates("api_key")).await.unwrap() }).await;
+    assert_eq!(rates.map(|rates| rates.len()), Ok(0));
+    assert_eq!(client.dry_run_calls()[0].request.url, "https://api.strike.me/v1/rates/ticker/");
+    assert!(mock.requests().is_empty());
+}